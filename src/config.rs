@@ -9,6 +9,30 @@ pub struct Config {
     pub colors: ColorConfig,
     pub user_host: UserHostConfig,
     pub ip: IpConfig,
+    #[serde(default)]
+    pub cpu: CpuConfig,
+    #[serde(default)]
+    pub cpu_usage: CpuUsageConfig,
+    #[serde(default)]
+    pub swap: SwapConfig,
+    #[serde(default)]
+    pub disk: DiskConfig,
+    #[serde(default)]
+    pub temp: TempConfig,
+    #[serde(default)]
+    pub power: PowerConfig,
+    #[serde(default)]
+    pub battery: BatteryConfig,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub net_speed: NetSpeedConfig,
+    #[serde(default)]
+    pub ports: PortsConfig,
+    #[serde(default)]
+    pub kernel: KernelConfig,
+    #[serde(default)]
+    pub top: TopConfig,
 }
 
 #[derive(Deserialize, Debug)]
@@ -27,6 +51,196 @@ pub struct UserHostConfig {
 #[derive(Deserialize, Debug)]
 pub struct IpConfig {
     pub public: bool,
+    #[serde(default)]
+    pub ipv6: bool,
+    #[serde(default)]
+    pub providers: Vec<String>,
+    #[serde(default = "IpConfig::default_timeout")]
+    pub timeout_secs: u64,
+}
+
+impl IpConfig {
+    fn default_timeout() -> u64 {
+        3
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct CpuConfig {
+    pub cores: bool,
+    pub freq: bool,
+    pub cache: bool,
+}
+
+impl Default for CpuConfig {
+    fn default() -> Self {
+        CpuConfig {
+            cores: true,
+            freq: true,
+            cache: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct CpuUsageConfig {
+    pub interval_ms: u64,
+    pub per_core: bool,
+}
+
+impl Default for CpuUsageConfig {
+    fn default() -> Self {
+        CpuUsageConfig {
+            interval_ms: 200,
+            per_core: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct SwapConfig {
+    pub devices: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct DiskConfig {
+    pub all: bool,
+    pub mounts: Vec<String>,
+    pub fs_type: bool,
+}
+
+impl Default for DiskConfig {
+    fn default() -> Self {
+        DiskConfig {
+            all: false,
+            mounts: vec![String::from("/")],
+            fs_type: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct TempConfig {
+    pub sensors: Vec<String>,
+    pub fahrenheit: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct PowerConfig {
+    pub interval_ms: u64,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        PowerConfig { interval_ms: 200 }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct BatteryConfig {
+    pub format: String,
+}
+
+impl Default for BatteryConfig {
+    fn default() -> Self {
+        BatteryConfig {
+            format: String::from("{status} ({percent}%)"),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub hide: Vec<String>,
+    pub ipv6: bool,
+    pub mac: bool,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        NetworkConfig {
+            hide: vec!["docker", "veth", "br-", "virbr"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            ipv6: true,
+            mac: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct NetSpeedConfig {
+    pub interval_ms: u64,
+    pub totals: bool,
+}
+
+impl Default for NetSpeedConfig {
+    fn default() -> Self {
+        NetSpeedConfig {
+            interval_ms: 500,
+            totals: true,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct PortsConfig {
+    pub udp: bool,
+    pub processes: bool,
+    pub loopback: bool,
+    pub max: usize,
+}
+
+impl Default for PortsConfig {
+    fn default() -> Self {
+        PortsConfig {
+            udp: false,
+            processes: false,
+            loopback: false,
+            max: 10,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct KernelConfig {
+    pub format: String,
+}
+
+impl Default for KernelConfig {
+    fn default() -> Self {
+        KernelConfig {
+            format: String::from("{release}"),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct TopConfig {
+    pub count: usize,
+    pub interval_ms: u64,
+}
+
+impl Default for TopConfig {
+    fn default() -> Self {
+        TopConfig {
+            count: 3,
+            interval_ms: 200,
+        }
+    }
 }
 
 impl Config {
@@ -70,7 +284,24 @@ impl Default for Config {
                 line_symbol: String::from("-"),
                 line_color: String::from("magenta"),
             },
-            ip: IpConfig { public: false },
+            ip: IpConfig {
+                public: false,
+                ipv6: false,
+                providers: Vec::new(),
+                timeout_secs: IpConfig::default_timeout(),
+            },
+            cpu: CpuConfig::default(),
+            cpu_usage: CpuUsageConfig::default(),
+            swap: SwapConfig::default(),
+            disk: DiskConfig::default(),
+            temp: TempConfig::default(),
+            power: PowerConfig::default(),
+            battery: BatteryConfig::default(),
+            network: NetworkConfig::default(),
+            net_speed: NetSpeedConfig::default(),
+            ports: PortsConfig::default(),
+            kernel: KernelConfig::default(),
+            top: TopConfig::default(),
         }
     }
 }
@@ -1,12 +1,23 @@
-#[cfg(feature = "battery")]
-use crate::stats::BatteryInfo;
 use crate::stats::{self, Distro};
 use crate::{
     config::Config,
     stats::{MachineInfo, UserInfo},
 };
 use colored::*;
-use stats::SysInfo;
+use stats::{IpError, LoadAvg, Mount, SysInfo, Systemctl, Temp, ToolkitSetting};
+use std::collections::HashMap;
+use std::time::Duration;
+
+// Replaces "{field}" placeholders in a user supplied format string
+fn fill_template(template: &str, fields: &[(&str, String)]) -> String {
+    fields
+        .iter()
+        .fold(template.to_string(), |output, (name, value)| {
+            output.replace(&format!("{{{}}}", name), value)
+        })
+        .trim()
+        .to_string()
+}
 
 pub struct Displayer {
     config: Config,
@@ -133,63 +144,472 @@ impl Displayer {
         None
     }
 
+    fn show_swap(&self) -> Option<String> {
+        let swap = stats::swap_info()?;
+        let mut output = format!(
+            "{} {} {}\n",
+            "swap".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            if swap.devices.is_empty() && swap.total.as_u64() == 0 {
+                "disabled".to_string()
+            } else {
+                format!("{} / {}", swap.used, swap.total)
+            }
+        );
+        if self.config.swap.devices {
+            for device in &swap.devices {
+                let mut line = format!("{} {} / {}", device.name, device.used, device.size);
+                if let Some(zram) = &device.zram {
+                    line += &format!(" ({:.1}x compression)", zram.ratio);
+                }
+                output += &format!("     {} {}\n", self.config.delimiter, line);
+            }
+        }
+        Some(output)
+    }
+
+    fn show_hugepages(&self) -> Option<String> {
+        let huge = stats::hugepages()?;
+        if huge.total == 0 {
+            return None;
+        }
+        let output = &format!(
+            "{} {} {} / {} pages of {} ({} reserved)\n",
+            "huge".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            huge.total - huge.free,
+            huge.total,
+            huge.page_size,
+            huge.reserved
+        );
+        Some(String::from(output))
+    }
+
     fn show_kern(&self, machine_info: &MachineInfo) -> String {
+        let format = &self.config.kernel.format;
+        let mut fields = vec![
+            ("release", machine_info.kernel.clone()),
+            ("version", machine_info.version.clone()),
+            ("arch", machine_info.arch.clone()),
+        ];
+        // Only read the extra details when the format asks for them
+        if ["{cmdline}", "{taint}", "{modules}", "{age}"]
+            .iter()
+            .any(|field| format.contains(field))
+        {
+            let details = stats::kernel_details(machine_info);
+            let age = details.build_date.map(|build_date| {
+                let days = stats::current_datetime()
+                    .naive_local()
+                    .date()
+                    .signed_duration_since(build_date)
+                    .num_days();
+                format!("{} days", days)
+            });
+            fields.push(("cmdline", details.cmdline.clone().unwrap_or_default()));
+            fields.push((
+                "taint",
+                match details.taint_flags() {
+                    Some(flags) if flags.is_empty() => String::from("untainted"),
+                    Some(flags) => flags,
+                    None => String::from("-"),
+                },
+            ));
+            fields.push((
+                "modules",
+                details
+                    .modules
+                    .map_or(String::from("-"), |modules| modules.to_string()),
+            ));
+            fields.push(("age", age.unwrap_or_else(|| String::from("-"))));
+        }
+
         let output = &format!(
             "{} {} {}\n",
             "kern".color(self.config.title_color.clone()),
             self.config.delimiter,
-            machine_info.kernel,
+            fill_template(format, &fields),
         );
         String::from(output)
     }
 
     fn show_ip(&self) -> Option<String> {
         let ip_type = if self.config.ip.public {
-            stats::IpType::Public
+            stats::IpType::Public {
+                providers: &self.config.ip.providers,
+                timeout: Duration::from_secs(self.config.ip.timeout_secs),
+            }
         } else {
             stats::IpType::Private
         };
-        let ip_info = stats::ip(ip_type);
+        let ip_info = stats::ip(ip_type, self.config.ip.ipv6);
         let output = &format!(
             "{}   {} {}\n",
             "ip".color(self.config.title_color.clone()),
             self.config.delimiter,
-            if let Some(ip_info) = ip_info {
-                ip_info.to_string()
-            } else {
-                "not connected".to_string()
+            match ip_info {
+                Ok(ip_info) => ip_info.to_string(),
+                Err(IpError::NotConnected) => "not connected".to_string(),
+                Err(IpError::LookupFailed) => "lookup failed".to_string(),
             }
         );
         Some(String::from(output))
     }
 
-    fn show_cpu(&self) -> Option<String> {
-        let cpu_info = stats::cpu_info();
-        if let Some(cpu_info) = cpu_info {
-            let output = &format!(
+    fn show_network(&self) -> Option<String> {
+        let mut output = String::new();
+        for interface in stats::net_interfaces()? {
+            let hidden = self
+                .config
+                .network
+                .hide
+                .iter()
+                .any(|prefix| interface.name.starts_with(prefix.as_str()));
+            if hidden {
+                continue;
+            }
+
+            let mut details = vec![interface.kind.to_string()];
+            if let Some(speed) = interface.speed {
+                details.push(format!("{} Mb/s", speed));
+            }
+            let mut line = format!("{} ({})", interface.name, details.join(", "));
+            let addrs = interface
+                .addrs
+                .iter()
+                .filter(|(addr, _)| self.config.network.ipv6 || addr.is_ipv4())
+                .map(|(addr, prefix)| format!("{}/{}", addr, prefix))
+                .collect::<Vec<String>>();
+            if !addrs.is_empty() {
+                line += &format!(" {}", addrs.join(", "));
+            }
+            if self.config.network.mac {
+                if let Some(mac) = &interface.mac {
+                    line += &format!(" [{}]", mac);
+                }
+            }
+            output += &format!(
                 "{}  {} {}\n",
-                "cpu".color(self.config.title_color.clone()),
+                "net".color(self.config.title_color.clone()),
                 self.config.delimiter,
-                cpu_info[0].model_name
+                line
             );
-            return Some(String::from(output));
         }
-        None
+        if output.is_empty() {
+            return None;
+        }
+        Some(output)
+    }
+
+    fn show_net_speed(&self) -> Option<String> {
+        // Uses the same interfaces as the network module
+        let interfaces = stats::net_interfaces()?
+            .into_iter()
+            .map(|interface| interface.name)
+            .filter(|name| {
+                !self
+                    .config
+                    .network
+                    .hide
+                    .iter()
+                    .any(|prefix| name.starts_with(prefix.as_str()))
+            })
+            .collect::<Vec<String>>();
+        let interval = Duration::from_millis(self.config.net_speed.interval_ms);
+
+        let mut output = String::new();
+        for speed in stats::net_speed(&interfaces, interval) {
+            let mut line = format!(
+                "{} ↓ {}/s ↑ {}/s",
+                speed.interface, speed.rx_rate, speed.tx_rate
+            );
+            if self.config.net_speed.totals {
+                line += &format!(" (total ↓ {} ↑ {})", speed.rx_total, speed.tx_total);
+            }
+            output += &format!(
+                "{} {} {}\n",
+                "rate".color(self.config.title_color.clone()),
+                self.config.delimiter,
+                line
+            );
+        }
+        if output.is_empty() {
+            return None;
+        }
+        Some(output)
+    }
+
+    fn show_route(&self) -> Option<String> {
+        let mut output = String::new();
+        for route in stats::default_routes() {
+            output += &format!(
+                "{}   {} {} via {}\n",
+                "gw".color(self.config.title_color.clone()),
+                self.config.delimiter,
+                route.gateway,
+                route.interface
+            );
+        }
+        if output.is_empty() {
+            return None;
+        }
+        Some(output)
+    }
+
+    fn show_dns(&self) -> Option<String> {
+        let dns = stats::dns()?;
+        if dns.nameservers.is_empty() {
+            return None;
+        }
+        let mut line = dns.nameservers.join(", ");
+        if !dns.search.is_empty() {
+            line += &format!(" (search {})", dns.search.join(" "));
+        }
+        let output = &format!(
+            "{}  {} {}\n",
+            "dns".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            line
+        );
+        Some(String::from(output))
+    }
+
+    fn show_ports(&self) -> Option<String> {
+        let owners = if self.config.ports.processes {
+            stats::socket_owners()
+        } else {
+            HashMap::new()
+        };
+
+        // Sockets bound to the same port on several addresses are listed once
+        let mut ports: Vec<(&str, u16, Option<&String>)> = Vec::new();
+        let sockets = stats::listening_sockets(self.config.ports.udp);
+        for socket in &sockets {
+            if !self.config.ports.loopback && socket.addr.is_loopback() {
+                continue;
+            }
+            if !ports
+                .iter()
+                .any(|(protocol, port, _)| *protocol == socket.protocol && *port == socket.port)
+            {
+                ports.push((socket.protocol, socket.port, owners.get(&socket.inode)));
+            }
+        }
+        if ports.is_empty() {
+            return None;
+        }
+
+        let mut list = ports
+            .iter()
+            .take(self.config.ports.max)
+            .map(|(protocol, port, owner)| {
+                let mut port = if self.config.ports.udp {
+                    format!("{}/{}", port, protocol)
+                } else {
+                    port.to_string()
+                };
+                if let Some(owner) = owner {
+                    port += &format!(" ({})", owner);
+                }
+                port
+            })
+            .collect::<Vec<String>>();
+        if ports.len() > self.config.ports.max {
+            list.push(String::from("..."));
+        }
+        let output = &format!(
+            "{} {} {} listening: {}\n",
+            "port".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            ports.len(),
+            list.join(", ")
+        );
+        Some(String::from(output))
+    }
+
+    fn show_wifi(&self) -> Option<String> {
+        let mut output = String::new();
+        for wifi in stats::wifi_info()? {
+            let mut line = wifi.ssid.clone().unwrap_or_else(|| wifi.interface.clone());
+            let mut details = Vec::new();
+            if let Some(quality) = wifi.quality {
+                details.push(format!("{}%", quality));
+            }
+            if let Some(signal) = wifi.signal {
+                details.push(format!("{} dBm", signal));
+            }
+            if let Some(band) = wifi.band() {
+                details.push(band.to_string());
+            }
+            if !details.is_empty() {
+                line += &format!(" ({})", details.join(", "));
+            }
+            output += &format!(
+                "{} {} {}\n",
+                "wifi".color(self.config.title_color.clone()),
+                self.config.delimiter,
+                line
+            );
+        }
+        if output.is_empty() {
+            return None;
+        }
+        Some(output)
+    }
+
+    fn show_cpu(&self) -> Option<String> {
+        let cpu_info = stats::cpu_info()?;
+        let mut cpu = cpu_info.model_name;
+        if self.config.cpu.cores {
+            cpu += &format!(" ({}C/{}T)", cpu_info.cores, cpu_info.threads);
+        }
+        if self.config.cpu.freq {
+            if let Some(cur_mhz) = cpu_info.cur_mhz {
+                cpu += &format!(" @ {:.2} GHz", cur_mhz / 1000.0);
+            }
+            if let Some(max_mhz) = cpu_info.max_mhz {
+                cpu += &format!(" (max {:.2} GHz)", max_mhz / 1000.0);
+            }
+        }
+        if self.config.cpu.cache {
+            let caches = vec![("L2", cpu_info.l2_cache), ("L3", cpu_info.l3_cache)]
+                .into_iter()
+                .filter_map(|(level, size)| Some(format!("{} {}", level, size?)))
+                .collect::<Vec<String>>();
+            if !caches.is_empty() {
+                cpu += &format!(" [{}]", caches.join(", "));
+            }
+        }
+
+        let output = &format!(
+            "{}  {} {}\n",
+            "cpu".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            cpu
+        );
+        Some(String::from(output))
+    }
+
+    fn show_cpu_usage(&self) -> Option<String> {
+        let interval = Duration::from_millis(self.config.cpu_usage.interval_ms);
+        let usage = stats::cpu_usage(interval)?;
+        let mut output = format!(
+            "{} {} {:.1}%\n",
+            "cpu%".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            usage.total
+        );
+        if self.config.cpu_usage.per_core {
+            for (core, core_usage) in usage.per_core.iter().enumerate() {
+                output += &format!(
+                    "{} {} {:.1}%\n",
+                    format!("{:<4}", format!("c{}", core)).color(self.config.title_color.clone()),
+                    self.config.delimiter,
+                    core_usage
+                );
+            }
+        }
+        Some(output)
+    }
+
+    fn show_load(&self, sys_info: &Option<SysInfo>) -> Option<String> {
+        let load = stats::load_avg().or_else(|| {
+            let (one, five, fifteen) = sys_info.as_ref()?.load_avg;
+            Some(LoadAvg {
+                one,
+                five,
+                fifteen,
+                running: None,
+                total: None,
+            })
+        })?;
+        let mut load_str = format!("{:.2} {:.2} {:.2}", load.one, load.five, load.fifteen);
+        if let (Some(running), Some(total)) = (load.running, load.total) {
+            load_str += &format!(" ({}/{} running)", running, total);
+        }
+        let output = &format!(
+            "{} {} {}\n",
+            "load".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            load_str
+        );
+        Some(String::from(output))
     }
 
     fn show_disk(&self) -> Option<String> {
-        let disk_usage = stats::disk_usage("/");
-        if let Some(disk_usage) = disk_usage {
-            let output = &format!(
-                "{} {} {} / {}\n",
-                "disk".color(self.config.title_color.clone()),
+        let mounts = if self.config.disk.all {
+            stats::real_mounts()?
+        } else {
+            let mut mounts = stats::mounts().unwrap_or_default();
+            self.config
+                .disk
+                .mounts
+                .iter()
+                .map(|mount_point| {
+                    // Later entries in the mount table shadow earlier ones
+                    let idx = mounts
+                        .iter()
+                        .rposition(|mount| &mount.mount_point == mount_point);
+                    match idx {
+                        Some(idx) => mounts.remove(idx),
+                        None => Mount {
+                            mount_point: mount_point.clone(),
+                            fs_type: String::new(),
+                        },
+                    }
+                })
+                .collect()
+        };
+
+        let mut output = String::new();
+        for mount in mounts {
+            if let Some(disk_usage) = stats::disk_usage(&mount.mount_point) {
+                let percent = (disk_usage.used.as_u64() * 100)
+                    .checked_div(disk_usage.total_size.as_u64())
+                    .unwrap_or(0);
+                let mut line = format!(
+                    "{} {} / {} ({}%)",
+                    mount.mount_point, disk_usage.used, disk_usage.total_size, percent
+                );
+                if self.config.disk.fs_type && !mount.fs_type.is_empty() {
+                    line += &format!(" [{}]", mount.fs_type);
+                }
+                output += &format!(
+                    "{} {} {}\n",
+                    "disk".color(self.config.title_color.clone()),
+                    self.config.delimiter,
+                    line
+                );
+            }
+        }
+        if output.is_empty() {
+            return None;
+        }
+        Some(output)
+    }
+
+    fn show_storage(&self) -> Option<String> {
+        let devices = stats::block_devices()?;
+        let mut output = String::new();
+        for device in devices {
+            let mut line = format!("{} {} {}", device.name, device.size, device.kind);
+            if let Some(model) = device.model {
+                line += &format!(" ({})", model);
+            }
+            if device.removable {
+                line += " [removable]";
+            }
+            output += &format!(
+                "{} {} {}\n",
+                "stor".color(self.config.title_color.clone()),
                 self.config.delimiter,
-                disk_usage.used,
-                disk_usage.total_size
+                line
             );
-            return Some(String::from(output));
         }
-        None
+        if output.is_empty() {
+            return None;
+        }
+        Some(output)
     }
 
     fn show_process(&self, sys_info: &Option<SysInfo>) -> Option<String> {
@@ -205,6 +625,36 @@ impl Displayer {
         None
     }
 
+    fn show_top(&self) -> String {
+        let interval = Duration::from_millis(self.config.top.interval_ms);
+        let stats = stats::process_stats(self.config.top.count, interval);
+        let by_mem = stats
+            .by_mem
+            .iter()
+            .map(|proc| format!("{} ({}) {}", proc.name, proc.pid, proc.rss))
+            .collect::<Vec<String>>();
+        let by_cpu = stats
+            .by_cpu
+            .iter()
+            .map(|proc| format!("{} ({}) {:.1}%", proc.name, proc.pid, proc.cpu))
+            .collect::<Vec<String>>();
+        let output = &format!(
+            "{}  {} {} total, {} running, {} zombie\n{}  {} {}\n{} {} {}\n",
+            "top".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            stats.total,
+            stats.running,
+            stats.zombies,
+            "rss".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            by_mem.join(", "),
+            "tcpu".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            by_cpu.join(", ")
+        );
+        String::from(output)
+    }
+
     fn show_arch(&self, machine_info: &MachineInfo) -> String {
         let output = &format!(
             "{} {} {}\n",
@@ -215,18 +665,80 @@ impl Displayer {
         String::from(output)
     }
 
+    fn format_temp(&self, temp: &Temp) -> String {
+        if self.config.temp.fahrenheit {
+            format!("{:.1}°F", temp.fahrenheit())
+        } else {
+            format!("{:.1}°C", temp.0)
+        }
+    }
+
     fn show_temp(&self) -> Option<String> {
-        let temp = stats::get_temp();
-        if let Some(temp) = temp {
+        if self.config.temp.sensors.is_empty() {
+            let temp = stats::get_temp()?;
             let output = &format!(
-                "{} {} {}°C\n",
+                "{} {} {}\n",
                 "temp".color(self.config.title_color.clone()),
                 self.config.delimiter,
-                temp.0 / 1000
+                self.format_temp(&temp)
             );
             return Some(String::from(output));
         }
-        None
+
+        // Sensors are selected either by chip name, by label or by "chip/label"
+        let mut output = String::new();
+        for sensor in stats::temp_sensors() {
+            let full_name = format!("{}/{}", sensor.chip, sensor.label);
+            let selected =
+                self.config.temp.sensors.iter().any(|name| {
+                    name == &sensor.chip || name == &sensor.label || name == &full_name
+                });
+            if selected {
+                output += &format!(
+                    "{} {} {} {}\n",
+                    "temp".color(self.config.title_color.clone()),
+                    self.config.delimiter,
+                    full_name,
+                    self.format_temp(&sensor.temp)
+                );
+            }
+        }
+        if output.is_empty() {
+            return None;
+        }
+        Some(output)
+    }
+
+    fn show_fans(&self) -> Option<String> {
+        let fans = stats::fans()
+            .into_iter()
+            .map(|fan| format!("{} {} RPM", fan.label, fan.rpm))
+            .collect::<Vec<String>>();
+        if fans.is_empty() {
+            return None;
+        }
+        let output = &format!(
+            "{}  {} {}\n",
+            "fan".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            fans.join(", ")
+        );
+        Some(String::from(output))
+    }
+
+    fn show_power(&self) -> Option<String> {
+        let interval = Duration::from_millis(self.config.power.interval_ms);
+        let domains = stats::power_draw(interval)?
+            .into_iter()
+            .map(|domain| format!("{} {:.1} W", domain.name, domain.watts))
+            .collect::<Vec<String>>();
+        let output = &format!(
+            "{}  {} {}\n",
+            "pwr".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            domains.join(", ")
+        );
+        Some(String::from(output))
     }
 
     fn show_locale(&self) -> Option<String> {
@@ -257,6 +769,272 @@ impl Displayer {
         None
     }
 
+    fn show_display(&self) -> Option<String> {
+        let mut output = String::new();
+        for monitor in stats::monitors()? {
+            let name = match (&monitor.manufacturer, &monitor.model) {
+                (_, Some(model)) => model.clone(),
+                (Some(manufacturer), None) => manufacturer.clone(),
+                (None, None) => monitor.connector.clone(),
+            };
+            let mode = |resolution: &str, refresh: Option<f64>| match refresh {
+                Some(refresh) => format!("{} @ {:.0} Hz", resolution, refresh),
+                None => resolution.to_string(),
+            };
+            let native = monitor
+                .native_resolution
+                .as_ref()
+                .map(|resolution| mode(resolution, monitor.native_refresh));
+
+            let mut line = name;
+            let mut details = vec![monitor.connector.clone()];
+            match (&monitor.resolution, native) {
+                (Some(resolution), native) => {
+                    let current = mode(resolution, monitor.refresh);
+                    line += &format!(" {}", current);
+                    // Only worth mentioning when it differs from the current mode
+                    if let Some(native) = native.filter(|native| *native != current) {
+                        details.push(format!("native {}", native));
+                    }
+                }
+                (None, Some(native)) => line += &format!(" {} native", native),
+                (None, None) => {}
+            }
+            if let Some(diagonal) = monitor.diagonal_inches() {
+                details.push(format!("{:.1}\"", diagonal));
+            }
+            if let Some(max_refresh) = monitor.max_refresh {
+                details.push(format!("up to {} Hz", max_refresh));
+            }
+            line += &format!(" ({})", details.join(", "));
+            output += &format!(
+                "{}  {} {}\n",
+                "dsp".color(self.config.title_color.clone()),
+                self.config.delimiter,
+                line
+            );
+        }
+        if output.is_empty() {
+            return None;
+        }
+        Some(output)
+    }
+
+    fn show_toolkit_setting(&self, title: &str, setting: &ToolkitSetting) -> Option<String> {
+        let value = match (&setting.gtk, &setting.qt) {
+            (Some(gtk), Some(qt)) if gtk == qt => format!("{} [GTK/Qt]", gtk),
+            (Some(gtk), Some(qt)) => format!("{} [GTK], {} [Qt]", gtk, qt),
+            (Some(gtk), None) => format!("{} [GTK]", gtk),
+            (None, Some(qt)) => format!("{} [Qt]", qt),
+            (None, None) => return None,
+        };
+        let output = &format!(
+            "{} {} {}\n",
+            format!("{:<4}", title).color(self.config.title_color.clone()),
+            self.config.delimiter,
+            value
+        );
+        Some(String::from(output))
+    }
+
+    fn show_board(&self) -> Option<String> {
+        let board = stats::board()?;
+        let mut line = vec![board.vendor, board.name]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<String>>()
+            .join(" ");
+        if let Some(version) = board.version {
+            line += &format!(" {}", version);
+        }
+        if let Some(chassis) = board.chassis {
+            line += &format!(" ({})", chassis);
+        }
+        let output = &format!(
+            "{}  {} {}\n",
+            "brd".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            line
+        );
+        Some(String::from(output))
+    }
+
+    fn show_bios(&self) -> Option<String> {
+        let bios = stats::bios()?;
+        let mut line = vec![bios.vendor, bios.version]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<String>>()
+            .join(" ");
+        if let Some(date) = bios.date {
+            line += &format!(" ({})", date);
+        }
+        let output = &format!(
+            "{} {} {}\n",
+            "bios".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            line
+        );
+        Some(String::from(output))
+    }
+
+    fn show_virt(&self) -> String {
+        let virt = stats::virt();
+        let line = match (virt.container, virt.hypervisor) {
+            (Some(container), Some(hypervisor)) => format!("{} on {}", container, hypervisor),
+            (Some(container), None) => container,
+            (None, Some(hypervisor)) => hypervisor,
+            (None, None) => String::from("bare metal"),
+        };
+        let output = &format!(
+            "{} {} {}\n",
+            "virt".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            line
+        );
+        String::from(output)
+    }
+
+    fn show_boot(&self) -> String {
+        let boot = stats::boot_info();
+        let mut details = Vec::new();
+        if let Some(boot_time) = boot.boot_time {
+            details.push(boot_time.format("%b %d %Y %H:%M").to_string());
+        }
+        details.push(String::from(if boot.uefi { "UEFI" } else { "legacy BIOS" }));
+        if let Some(secure_boot) = boot.secure_boot {
+            details.push(format!(
+                "Secure Boot {}",
+                if secure_boot { "on" } else { "off" }
+            ));
+        }
+        if let Some(bootloader) = boot.bootloader {
+            details.push(bootloader);
+        }
+        let output = &format!(
+            "{} {} {}\n",
+            "boot".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            details.join(", ")
+        );
+        String::from(output)
+    }
+
+    fn show_security(&self) -> String {
+        let security = stats::security();
+        let mut details = Vec::new();
+        if !security.lsms.is_empty() {
+            details.push(format!("lsm {}", security.lsms.join(",")));
+        }
+        if let Some(selinux) = security.selinux {
+            details.push(format!("SELinux {}", selinux));
+        }
+        match security.apparmor {
+            Some(Some(profiles)) => details.push(format!("AppArmor ({} profiles)", profiles)),
+            Some(None) => details.push(String::from("AppArmor")),
+            None => {}
+        }
+        if let Some(lockdown) = security.lockdown {
+            details.push(format!("lockdown {}", lockdown));
+        }
+        if details.is_empty() {
+            details.push(String::from("no LSM information"));
+        }
+
+        let vulns = security.vulnerabilities;
+        let mut vuln_line = format!(
+            "{} mitigated, {} not affected",
+            vulns.mitigated, vulns.not_affected
+        );
+        if !vulns.vulnerable.is_empty() {
+            vuln_line += &format!(
+                ", {} ({})",
+                format!("{} vulnerable", vulns.vulnerable.len()).red(),
+                vulns.vulnerable.join(", ")
+            );
+        }
+        if vulns.unknown > 0 {
+            vuln_line += &format!(", {} unknown", vulns.unknown);
+        }
+
+        let output = &format!(
+            "{}  {} {}\n{} {} {}\n",
+            "sec".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            details.join(", "),
+            "vuln".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            vuln_line
+        );
+        String::from(output)
+    }
+
+    fn show_users(&self, user_info: &Option<UserInfo>) -> Option<String> {
+        let user = user_info.as_ref()?;
+        let sessions = stats::sessions(&user.name)?;
+        let mut output = format!(
+            "{} {} {} sessions, {} users ({})\n",
+            "usrs".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            sessions.sessions,
+            sessions.users.len(),
+            sessions.users.join(", ")
+        );
+        if let Some(last_login) = sessions.last_login {
+            let mut line = last_login.time.format("%b %d %Y %H:%M").to_string();
+            if !last_login.host.is_empty() {
+                line += &format!(" from {}", last_login.host);
+            } else if !last_login.line.is_empty() {
+                line += &format!(" on {}", last_login.line);
+            }
+            output += &format!(
+                "{} {} {}\n",
+                "last".color(self.config.title_color.clone()),
+                self.config.delimiter,
+                line
+            );
+        }
+        Some(output)
+    }
+
+    fn show_init(&self) -> Option<String> {
+        let init = stats::init_system()?;
+        let mut line = init.name;
+        if let Some(version) = init.version {
+            line += &format!(" {}", version);
+        }
+        let output = &format!(
+            "{} {} {}\n",
+            "init".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            line
+        );
+        Some(String::from(output))
+    }
+
+    fn show_services(&self) -> Option<String> {
+        // Only systemd can be queried for unit states so far
+        if stats::init_comm()? != "systemd" {
+            return None;
+        }
+        let services = stats::services(&Systemctl)?;
+        let mut line = format!("{} running", services.running);
+        if !services.failed.is_empty() {
+            line += &format!(
+                ", {} ({})",
+                format!("{} failed", services.failed.len()).red(),
+                services.failed.join(", ")
+            );
+        }
+        let output = &format!(
+            "{}  {} {}\n",
+            "svc".color(self.config.title_color.clone()),
+            self.config.delimiter,
+            line
+        );
+        Some(String::from(output))
+    }
+
     fn show_time(&self) -> String {
         let dt = stats::current_datetime();
         let output = &format!(
@@ -279,17 +1057,56 @@ impl Displayer {
         String::from(output)
     }
 
-    #[cfg(feature = "battery")]
-    fn show_battery(&self) -> String {
-        let batt: BatteryInfo = stats::battery_info().expect("");
-        let output = &format!(
-            "{}  {} {} ({}%)\n",
-            "bat".color(self.config.title_color.clone()),
-            self.config.delimiter,
-            batt.status,
-            batt.percent
-        );
-        String::from(output)
+    fn show_battery(&self) -> Option<String> {
+        let power_supply = stats::power_supply()?;
+        let mut output = String::new();
+        for batt in &power_supply.batteries {
+            let fields = [
+                ("name", batt.name.clone()),
+                ("status", batt.status.to_string()),
+                ("percent", batt.percent.to_string()),
+                (
+                    "health",
+                    batt.health()
+                        .map_or(String::from("-"), |health| format!("{:.0}%", health)),
+                ),
+                (
+                    "cycles",
+                    batt.cycle_count
+                        .map_or(String::from("-"), |cycles| cycles.to_string()),
+                ),
+                (
+                    "time",
+                    batt.time_remaining().map_or(String::from("-"), |time| {
+                        // Round down to whole minutes
+                        let time = Duration::from_secs(time.as_secs() / 60 * 60);
+                        humantime::format_duration(time).to_string()
+                    }),
+                ),
+                (
+                    "power",
+                    batt.power_now
+                        .map_or(String::from("-"), |power| format!("{:.1} W", power)),
+                ),
+                (
+                    "ac",
+                    match power_supply.ac_online {
+                        Some(true) => String::from("AC"),
+                        _ => String::new(),
+                    },
+                ),
+            ];
+            output += &format!(
+                "{}  {} {}\n",
+                "bat".color(self.config.title_color.clone()),
+                self.config.delimiter,
+                fill_template(&self.config.battery.format, &fields)
+            );
+        }
+        if output.is_empty() {
+            return None;
+        }
+        Some(output)
     }
 
     pub fn fetch(&self) {
@@ -298,6 +1115,7 @@ impl Displayer {
         let distro = stats::distro();
         let machine_info = stats::machine_info();
         let sys_info = stats::sysinfo();
+        let mut desktop_theme = None;
 
         for module in &self.config.modules {
             match module.as_str() {
@@ -319,32 +1137,116 @@ impl Displayer {
                 "memory" => {
                     output += &self.show_mem().unwrap();
                 }
+                "swap" => {
+                    output += &self.show_swap().unwrap_or_default();
+                }
+                "hugepages" => {
+                    output += &self.show_hugepages().unwrap_or_default();
+                }
                 "kernel" => {
                     output += &self.show_kern(&machine_info);
                 }
                 "ip" => {
                     output += &self.show_ip().unwrap();
                 }
+                "network" => {
+                    output += &self.show_network().unwrap_or_default();
+                }
+                "net_speed" => {
+                    output += &self.show_net_speed().unwrap_or_default();
+                }
+                "route" => {
+                    output += &self.show_route().unwrap_or_default();
+                }
+                "dns" => {
+                    output += &self.show_dns().unwrap_or_default();
+                }
+                "ports" => {
+                    output += &self.show_ports().unwrap_or_default();
+                }
+                "wifi" => {
+                    output += &self.show_wifi().unwrap_or_default();
+                }
                 "cpu" => {
-                    output += &self.show_cpu().unwrap();
+                    output += &self.show_cpu().unwrap_or_default();
+                }
+                "cpu_usage" => {
+                    output += &self.show_cpu_usage().unwrap_or_default();
+                }
+                "load" => {
+                    output += &self.show_load(&sys_info).unwrap_or_default();
                 }
                 "disk_usage" => {
-                    output += &self.show_disk().unwrap();
+                    output += &self.show_disk().unwrap_or_default();
+                }
+                "storage" => {
+                    output += &self.show_storage().unwrap_or_default();
                 }
                 "process_num" => {
                     output += &self.show_process(&sys_info).unwrap();
                 }
+                "top" => {
+                    output += &self.show_top();
+                }
                 "arch" => {
                     output += &self.show_arch(&machine_info);
                 }
                 "temp" => {
-                    output += &self.show_temp().unwrap();
+                    output += &self.show_temp().unwrap_or_default();
+                }
+                "fan" => {
+                    output += &self.show_fans().unwrap_or_default();
+                }
+                "power" => {
+                    output += &self.show_power().unwrap_or_default();
                 }
                 "locale" => {
                     output += &self.show_locale().unwrap();
                 }
                 "device_name" => {
-                    output += &self.show_device().unwrap();
+                    output += &self.show_device().unwrap_or_default();
+                }
+                "display" => {
+                    output += &self.show_display().unwrap_or_default();
+                }
+                "theme" | "icons" | "cursor" | "font" => {
+                    if let Some(info) = &user_info {
+                        let theme =
+                            desktop_theme.get_or_insert_with(|| stats::desktop_theme(&info.home));
+                        let (title, setting) = match module.as_str() {
+                            "theme" => ("thm", &theme.theme),
+                            "icons" => ("icon", &theme.icons),
+                            "cursor" => ("cur", &theme.cursor),
+                            _ => ("font", &theme.font),
+                        };
+                        output += &self
+                            .show_toolkit_setting(title, setting)
+                            .unwrap_or_default();
+                    }
+                }
+                "board" => {
+                    output += &self.show_board().unwrap_or_default();
+                }
+                "bios" => {
+                    output += &self.show_bios().unwrap_or_default();
+                }
+                "virt" => {
+                    output += &self.show_virt();
+                }
+                "boot" => {
+                    output += &self.show_boot();
+                }
+                "security" => {
+                    output += &self.show_security();
+                }
+                "users" => {
+                    output += &self.show_users(&user_info).unwrap_or_default();
+                }
+                "init" => {
+                    output += &self.show_init().unwrap_or_default();
+                }
+                "services" => {
+                    output += &self.show_services().unwrap_or_default();
                 }
                 "time" => {
                     output += &self.show_time();
@@ -352,9 +1254,8 @@ impl Displayer {
                 "date" => {
                     output += &self.show_date();
                 }
-                #[cfg(feature = "battery")]
                 "battery" => {
-                    output += &self.show_battery();
+                    output += &self.show_battery().unwrap_or_default();
                 }
                 _ => {}
             }
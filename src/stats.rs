@@ -1,39 +1,109 @@
 extern crate minreq;
 
 use bytesize::ByteSize;
-use chrono::prelude::{DateTime, Local};
+use chrono::prelude::{DateTime, Local, NaiveDate, TimeZone};
 use isolang::Language;
-#[cfg(feature = "battery")]
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::convert::{TryFrom, TryInto};
 use std::fmt::Display;
 use std::fs;
-use std::path::PathBuf;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
-use std::{collections::HashMap, net::Ipv4Addr};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
 
 // TODO:
 // Try to utilize async and multi-threading for better performance
 
-pub enum IpType {
-    Public,
+pub enum IpType<'a> {
+    // Queries the given echo services in order, falling back to the
+    // built in ones if none are given
+    Public {
+        providers: &'a [String],
+        timeout: Duration,
+    },
     Private,
 }
 
+pub enum IpError {
+    NotConnected,
+    LookupFailed,
+}
+
+const PUBLIC_IPV4_PROVIDERS: [&str; 3] = [
+    "https://ifconfig.me/ip",
+    "https://api.ipify.org",
+    "https://ipv4.icanhazip.com",
+];
+
+const PUBLIC_IPV6_PROVIDERS: [&str; 2] = ["https://api6.ipify.org", "https://ipv6.icanhazip.com"];
+
 pub struct CpuInfo {
     pub model_name: String,
-    pub cpu_mhz: f64,
+    pub cores: usize,
+    pub threads: usize,
+    pub cur_mhz: Option<f64>,
+    pub max_mhz: Option<f64>,
+    pub l2_cache: Option<ByteSize>,
+    pub l3_cache: Option<ByteSize>,
 }
 
 pub struct MemInfo<T> {
     pub total: T,
+    #[allow(dead_code)]
     pub avail: T,
+    #[allow(dead_code)]
     pub cached: T,
+    #[allow(dead_code)]
     pub buffers: T,
     pub used: T,
 }
 
+pub struct SwapInfo<T> {
+    pub total: T,
+    pub used: T,
+    pub devices: Vec<SwapDevice<T>>,
+}
+
+pub struct SwapDevice<T> {
+    pub name: String,
+    pub size: T,
+    pub used: T,
+    pub zram: Option<ZramInfo>,
+}
+
+pub struct ZramInfo {
+    pub ratio: f64,
+}
+
+pub struct HugePages<T> {
+    pub total: u64,
+    pub free: u64,
+    pub reserved: u64,
+    pub page_size: T,
+}
+
+pub struct CpuUsage {
+    pub total: f64,
+    pub per_core: Vec<f64>,
+}
+
+pub struct LoadAvg {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+    pub running: Option<u32>,
+    pub total: Option<u32>,
+}
+
 pub struct SysInfo {
     pub uptime: Duration,
     pub process_num: u16,
+    pub load_avg: (f64, f64, f64),
 }
 
 pub struct UserInfo {
@@ -45,45 +115,439 @@ pub struct UserInfo {
 pub struct MachineInfo {
     pub arch: String,
     pub kernel: String,
+    pub version: String,
     pub nodename: String,
 }
 
+pub struct KernelDetails {
+    pub cmdline: Option<String>,
+    pub taint: Option<u64>,
+    pub modules: Option<usize>,
+    pub build_date: Option<NaiveDate>,
+}
+
+impl KernelDetails {
+    // Flag letters as printed in kernel oops messages, indexed by bit, see
+    // Documentation/admin-guide/tainted-kernels.rst
+    const TAINT_FLAGS: [char; 19] = [
+        'P', 'F', 'S', 'R', 'M', 'B', 'U', 'D', 'A', 'W', 'C', 'I', 'O', 'E', 'L', 'K', 'X', 'T',
+        'N',
+    ];
+
+    pub fn taint_flags(&self) -> Option<String> {
+        let taint = self.taint?;
+        Some(
+            Self::TAINT_FLAGS
+                .iter()
+                .enumerate()
+                .filter(|(bit, _)| taint & (1 << bit) != 0)
+                .map(|(_, flag)| *flag)
+                .collect(),
+        )
+    }
+}
+
 pub struct Distro {
     pub name: String,
+    #[allow(dead_code)]
     pub color: String,
 }
 
 pub struct LocaleInfo {
     pub locale: String,
+    #[allow(dead_code)]
     pub hr_lang: String,
 }
 
 pub struct FsInfo<T> {
     pub total_size: T,
+    #[allow(dead_code)]
     pub free: T,
     pub used: T,
 }
 
+pub struct Mount {
+    pub mount_point: String,
+    pub fs_type: String,
+}
+
+pub struct BlockDevice<T> {
+    pub name: String,
+    pub model: Option<String>,
+    pub size: T,
+    pub kind: DiskKind,
+    pub removable: bool,
+}
+
+pub enum DiskKind {
+    Hdd,
+    Ssd,
+    Nvme,
+}
+
+impl Display for DiskKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Hdd => "HDD",
+                Self::Ssd => "SSD",
+                Self::Nvme => "NVMe",
+            }
+        )
+    }
+}
+
+pub struct Fan {
+    pub label: String,
+    pub rpm: u32,
+}
+
+pub struct PowerDomain {
+    pub name: String,
+    pub watts: f64,
+}
+
+pub struct NetInterface {
+    pub name: String,
+    pub kind: InterfaceKind,
+    pub mac: Option<String>,
+    // Link speed in Mb/s, only known for physical links
+    pub speed: Option<u32>,
+    // Addresses with their prefix length
+    pub addrs: Vec<(IpAddr, u8)>,
+}
+
+pub enum InterfaceKind {
+    Ethernet,
+    Wifi,
+    Bridge,
+    Tun,
+    Wireguard,
+    Virtual,
+}
+
+impl Display for InterfaceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Ethernet => "ethernet",
+                Self::Wifi => "wifi",
+                Self::Bridge => "bridge",
+                Self::Tun => "tun",
+                Self::Wireguard => "wireguard",
+                Self::Virtual => "virtual",
+            }
+        )
+    }
+}
+
+pub struct NetSpeed<T> {
+    pub interface: String,
+    // Rates are per second, totals count from when the interface was created
+    pub rx_rate: T,
+    pub tx_rate: T,
+    pub rx_total: T,
+    pub tx_total: T,
+}
+
+pub struct Route {
+    pub interface: String,
+    pub gateway: IpAddr,
+    pub metric: u32,
+}
+
+pub struct DnsInfo {
+    pub nameservers: Vec<String>,
+    pub search: Vec<String>,
+}
+
+pub struct ListeningSocket {
+    pub protocol: &'static str,
+    pub addr: IpAddr,
+    pub port: u16,
+    pub inode: u64,
+}
+
+pub struct WifiInfo {
+    pub interface: String,
+    pub ssid: Option<String>,
+    // Link quality in percent and signal level in dBm
+    pub quality: Option<u8>,
+    pub signal: Option<i32>,
+    pub freq_mhz: Option<u32>,
+}
+
+impl WifiInfo {
+    pub fn band(&self) -> Option<&'static str> {
+        Some(match self.freq_mhz? {
+            0..=2999 => "2.4 GHz",
+            3000..=5924 => "5 GHz",
+            _ => "6 GHz",
+        })
+    }
+}
+
+pub struct Monitor {
+    pub connector: String,
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    // Mode the connector is driven at, needs access to the card node
+    pub resolution: Option<String>,
+    pub refresh: Option<f64>,
+    // The panel's preferred mode
+    pub native_resolution: Option<String>,
+    pub native_refresh: Option<f64>,
+    pub max_refresh: Option<u32>,
+    // Physical size in millimetres
+    pub size_mm: Option<(u32, u32)>,
+}
+
+impl Monitor {
+    pub fn diagonal_inches(&self) -> Option<f64> {
+        let (width, height) = self.size_mm?;
+        Some(((width * width + height * height) as f64).sqrt() / 25.4)
+    }
+}
+
+// A desktop setting as configured for GTK and Qt applications
+#[derive(Default)]
+pub struct ToolkitSetting {
+    pub gtk: Option<String>,
+    pub qt: Option<String>,
+}
+
+#[derive(Default)]
+pub struct DesktopTheme {
+    pub theme: ToolkitSetting,
+    pub icons: ToolkitSetting,
+    pub cursor: ToolkitSetting,
+    pub font: ToolkitSetting,
+}
+
 pub struct Color(pub String);
 
 pub struct DeviceInfo(pub String);
 
-pub struct Temp(pub i32);
+pub struct BoardInfo {
+    pub vendor: Option<String>,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub chassis: Option<ChassisType>,
+}
+
+pub struct BiosInfo {
+    pub vendor: Option<String>,
+    pub version: Option<String>,
+    pub date: Option<String>,
+}
+
+pub struct VirtInfo {
+    pub hypervisor: Option<String>,
+    pub container: Option<String>,
+}
+
+pub struct InitInfo {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+pub struct ServiceStatus {
+    pub running: usize,
+    pub failed: Vec<String>,
+}
+
+// Source of unit states, implemented by each supported service manager so
+// the query can be swapped out
+pub trait ServiceManager {
+    fn running_units(&self) -> Option<Vec<String>>;
+    fn failed_units(&self) -> Option<Vec<String>>;
+}
+
+pub struct Systemctl;
+
+#[derive(Deserialize)]
+struct SystemdUnit {
+    unit: String,
+}
+
+// Unit names from "systemctl list-units --output=json"
+fn parse_units(json: &[u8]) -> Option<Vec<String>> {
+    let units = serde_json::from_slice::<Vec<SystemdUnit>>(json).ok()?;
+    Some(units.into_iter().map(|unit| unit.unit).collect())
+}
+
+impl Systemctl {
+    fn list_units(&self, args: &[&str]) -> Option<Vec<String>> {
+        let output = std::process::Command::new("systemctl")
+            .args(["list-units", "--output=json", "--no-pager"])
+            .args(args)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        parse_units(&output.stdout)
+    }
+}
+
+impl ServiceManager for Systemctl {
+    fn running_units(&self) -> Option<Vec<String>> {
+        self.list_units(&["--type=service", "--state=running"])
+    }
+
+    fn failed_units(&self) -> Option<Vec<String>> {
+        self.list_units(&["--state=failed"])
+    }
+}
+
+pub struct BootInfo {
+    pub boot_time: Option<DateTime<Local>>,
+    pub uefi: bool,
+    pub secure_boot: Option<bool>,
+    pub bootloader: Option<String>,
+}
+
+pub struct SecurityInfo {
+    pub lsms: Vec<String>,
+    // "enforcing" or "permissive", None when SELinux isn't loaded
+    pub selinux: Option<String>,
+    // Number of loaded profiles if AppArmor is enabled, which is only
+    // readable by root
+    pub apparmor: Option<Option<usize>>,
+    pub lockdown: Option<String>,
+    pub vulnerabilities: VulnSummary,
+}
+
+#[derive(Default)]
+pub struct VulnSummary {
+    pub not_affected: usize,
+    pub mitigated: usize,
+    pub vulnerable: Vec<String>,
+    // Unreadable, empty or "Unknown: ..." statuses
+    pub unknown: usize,
+}
+
+pub struct UtmpEntry {
+    pub kind: i16,
+    pub pid: i32,
+    pub line: String,
+    pub user: String,
+    pub host: String,
+    pub time: DateTime<Local>,
+}
+
+pub struct SessionInfo {
+    pub sessions: usize,
+    pub users: Vec<String>,
+    pub last_login: Option<UtmpEntry>,
+}
+
+#[derive(Clone)]
+pub struct ProcessUsage<T> {
+    pub pid: u32,
+    pub name: String,
+    pub rss: T,
+    // Share of one CPU over the sampling interval, in percent
+    pub cpu: f64,
+}
+
+pub struct ProcessStats<T> {
+    pub total: usize,
+    pub running: usize,
+    pub zombies: usize,
+    pub by_mem: Vec<ProcessUsage<T>>,
+    pub by_cpu: Vec<ProcessUsage<T>>,
+}
+
+pub enum ChassisType {
+    Desktop,
+    Laptop,
+    Tablet,
+    Server,
+    Other,
+}
+
+impl Display for ChassisType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Desktop => "desktop",
+                Self::Laptop => "laptop",
+                Self::Tablet => "tablet",
+                Self::Server => "server",
+                Self::Other => "other",
+            }
+        )
+    }
+}
+
+// Degrees Celsius
+pub struct Temp(pub f64);
+
+impl Temp {
+    pub fn fahrenheit(&self) -> f64 {
+        self.0 * 9.0 / 5.0 + 32.0
+    }
+}
+
+pub struct TempSensor {
+    pub chip: String,
+    pub label: String,
+    pub temp: Temp,
+}
 
-#[cfg(feature = "battery")]
 pub struct BatteryInfo {
+    pub name: String,
     pub status: BatteryStatus,
     pub percent: u8,
+    // Energy figures are in watt hours, power in watts
+    pub energy_now: Option<f64>,
+    pub energy_full: Option<f64>,
+    pub energy_full_design: Option<f64>,
+    pub power_now: Option<f64>,
+    pub cycle_count: Option<u32>,
+}
+
+impl BatteryInfo {
+    // Remaining capacity compared to the design capacity, in percent
+    pub fn health(&self) -> Option<f64> {
+        let design = self.energy_full_design.filter(|design| *design > 0.0)?;
+        Some(self.energy_full? / design * 100.0)
+    }
+
+    // Estimated time until the battery is empty or fully charged,
+    // extrapolated from the current power draw
+    pub fn time_remaining(&self) -> Option<Duration> {
+        let power_now = self.power_now.filter(|power| *power > 0.0)?;
+        let energy_now = self.energy_now?;
+        let hours = match self.status {
+            BatteryStatus::Discharging => energy_now / power_now,
+            BatteryStatus::Charging => (self.energy_full? - energy_now).max(0.0) / power_now,
+            _ => return None,
+        };
+        Some(Duration::from_secs((hours * 3600.0) as u64))
+    }
+}
+
+pub struct PowerSupplyInfo {
+    pub batteries: Vec<BatteryInfo>,
+    pub ac_online: Option<bool>,
 }
 
-#[cfg(feature = "battery")]
 pub enum BatteryStatus {
     Charging,
     Discharging,
+    NotCharging,
     Full,
+    Unknown,
 }
 
-#[cfg(feature = "battery")]
 impl Display for BatteryStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -92,7 +556,9 @@ impl Display for BatteryStatus {
             match self {
                 Self::Charging => "charging",
                 Self::Discharging => "discharging",
+                Self::NotCharging => "not charging",
                 Self::Full => "full",
+                Self::Unknown => "unknown",
             }
         )
     }
@@ -104,42 +570,229 @@ pub fn get_env(key: &str) -> Option<String> {
     std::env::var(key).ok()
 }
 
+fn read_trimmed<P: AsRef<Path>>(path: P) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|data| data.trim().to_string())
+}
+
+fn read_num<T: std::str::FromStr, P: AsRef<Path>>(path: P) -> Option<T> {
+    read_trimmed(path)?.parse::<T>().ok()
+}
+
+// Parses a colon separated "key : value" block, as used by /proc/cpuinfo
+fn parse_kv_block(block: &str) -> HashMap<&str, &str> {
+    block
+        .lines()
+        .filter_map(|line| {
+            let mut kv = line.splitn(2, ':');
+            Some((kv.next()?.trim(), kv.next()?.trim()))
+        })
+        .collect()
+}
+
+// Sizes in sysfs cache descriptions look like "512K" or "32M"
+fn parse_cache_size(size: &str) -> Option<ByteSize> {
+    let (num, mult) = match size.chars().last()? {
+        'K' => (&size[..size.len() - 1], 1024),
+        'M' => (&size[..size.len() - 1], 1024 * 1024),
+        _ => (size, 1),
+    };
+    Some(ByteSize::b(num.parse::<u64>().ok()? * mult))
+}
+
+fn clean_cpu_name(name: &str) -> String {
+    let name = name
+        .replace("(R)", "")
+        .replace("(r)", "")
+        .replace("(TM)", "")
+        .replace("(tm)", "");
+    // Drop the nominal clock speed, it is reported separately
+    let name = name.split(" @ ").next().unwrap_or("");
+    name.split_whitespace()
+        .filter(|word| *word != "CPU")
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
 // Functions for getting statistics and information about the system
 // Used by the rfetch frontend (might seperate this into another crate)
 
-pub fn cpu_info() -> Option<Vec<CpuInfo>> {
+pub fn cpu_info() -> Option<CpuInfo> {
     let data = fs::read_to_string("/proc/cpuinfo").ok()?;
-
     let blocks = data
-        .split('\n')
-        .filter(|elm| elm.starts_with("model name") || elm.starts_with("cpu MHz"))
-        .map(|elm| elm.split(": ").nth(1))
-        .collect::<Vec<Option<&str>>>();
-
-    blocks
-        .chunks(2)
-        .map(|ck| -> Option<CpuInfo> {
-            Some(CpuInfo {
-                model_name: String::from(ck[0]?),
-                cpu_mhz: ck[1]?.parse::<f64>().ok()?,
-            })
+        .split("\n\n")
+        .map(parse_kv_block)
+        .filter(|block| !block.is_empty())
+        .collect::<Vec<HashMap<&str, &str>>>();
+
+    // x86 reports "model name" per processor, ARM usually only has a
+    // "Hardware" line in the trailing block or "CPU part" identifiers
+    let model_name = blocks
+        .iter()
+        .find_map(|block| block.get("model name").or_else(|| block.get("Processor")))
+        .or_else(|| blocks.iter().find_map(|block| block.get("Hardware")))
+        .map(|name| clean_cpu_name(name))
+        .or_else(|| {
+            let block = blocks.first()?;
+            Some(format!(
+                "ARM {} {}",
+                block.get("CPU implementer")?,
+                block.get("CPU part")?
+            ))
+        })?;
+
+    let cpu_dir = Path::new("/sys/devices/system/cpu");
+    // sysfs can be missing in containers, /proc/cpuinfo is used instead then
+    let cpus = fs::read_dir(cpu_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| {
+                    name.strip_prefix("cpu")
+                        .is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+                })
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_default();
+
+    let cores = cpus
+        .iter()
+        .filter_map(|cpu| {
+            let topology = cpu_dir.join(cpu).join("topology");
+            Some((
+                read_num::<i64, _>(topology.join("physical_package_id"))?,
+                read_num::<i64, _>(topology.join("core_id"))?,
+            ))
+        })
+        .collect::<HashSet<(i64, i64)>>()
+        .len();
+    let threads = if cpus.is_empty() {
+        blocks
+            .iter()
+            .filter(|block| block.contains_key("processor"))
+            .count()
+    } else {
+        cpus.len()
+    };
+    // Without topology fall back to the per package "cpu cores" count
+    let cores = match cores {
+        0 => blocks
+            .iter()
+            .find_map(|block| block.get("cpu cores")?.parse::<usize>().ok())
+            .unwrap_or(threads),
+        cores => cores,
+    };
+
+    let cpufreq = cpu_dir.join("cpu0/cpufreq");
+    let cur_mhz = read_num::<f64, _>(cpufreq.join("scaling_cur_freq"))
+        .map(|khz| khz / 1000.0)
+        .or_else(|| {
+            blocks
+                .iter()
+                .find_map(|block| block.get("cpu MHz")?.parse::<f64>().ok())
+        });
+    let max_mhz = read_num::<f64, _>(cpufreq.join("cpuinfo_max_freq")).map(|khz| khz / 1000.0);
+
+    let mut l2_cache = None;
+    let mut l3_cache = None;
+    if let Ok(entries) = fs::read_dir(cpu_dir.join("cpu0/cache")) {
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let index = entry.path();
+            let size = read_trimmed(index.join("size")).and_then(|s| parse_cache_size(&s));
+            match read_trimmed(index.join("level")).as_deref() {
+                Some("2") => l2_cache = size,
+                Some("3") => l3_cache = size,
+                _ => {}
+            }
+        }
+    }
+
+    Some(CpuInfo {
+        model_name,
+        cores: if cores == 0 { threads } else { cores },
+        threads,
+        cur_mhz,
+        max_mhz,
+        l2_cache,
+        l3_cache,
+    })
+}
+
+// Returns (busy, total) jiffies for the aggregate "cpu" line followed by each core
+fn cpu_times() -> Option<Vec<(u64, u64)>> {
+    let data = fs::read_to_string("/proc/stat").ok()?;
+    data.lines()
+        .filter(|line| line.starts_with("cpu"))
+        .map(|line| {
+            let times = line
+                .split_whitespace()
+                .skip(1)
+                .map(|time| time.parse::<u64>().ok())
+                .collect::<Option<Vec<u64>>>()?;
+            // idle and iowait are the 4th and 5th columns, guest time is
+            // already accounted for in user and nice
+            let total = times.iter().take(8).sum::<u64>();
+            let idle = times.get(3)? + times.get(4).unwrap_or(&0);
+            Some((total - idle, total))
         })
         .collect()
 }
 
-pub fn mem_info() -> Option<MemInfo<ByteSize>> {
+pub fn cpu_usage(interval: Duration) -> Option<CpuUsage> {
+    let before = cpu_times()?;
+    std::thread::sleep(interval);
+    let after = cpu_times()?;
+
+    let mut usage = before.iter().zip(after.iter()).map(|(prev, cur)| {
+        let busy = cur.0.saturating_sub(prev.0) as f64;
+        let total = cur.1.saturating_sub(prev.1) as f64;
+        if total > 0.0 {
+            busy / total * 100.0
+        } else {
+            0.0
+        }
+    });
+
+    Some(CpuUsage {
+        total: usage.next()?,
+        per_core: usage.collect(),
+    })
+}
+
+pub fn load_avg() -> Option<LoadAvg> {
+    let data = fs::read_to_string("/proc/loadavg").ok()?;
+    let fields = data.split_whitespace().collect::<Vec<&str>>();
+    let mut tasks = fields.get(3)?.split('/').map(|n| n.parse::<u32>().ok());
+
+    Some(LoadAvg {
+        one: fields.first()?.parse().ok()?,
+        five: fields.get(1)?.parse().ok()?,
+        fifteen: fields.get(2)?.parse().ok()?,
+        running: tasks.next().flatten(),
+        total: tasks.next().flatten(),
+    })
+}
+
+fn meminfo() -> Option<HashMap<String, Option<u64>>> {
     let data = fs::read_to_string("/proc/meminfo").ok()?;
-    let mem = data
-        .split('\n')
-        .map(|kv| kv.split_whitespace().take(2).collect::<Vec<&str>>())
-        .filter(|elm| !elm.is_empty())
-        .map(|elm| -> (String, Option<u64>) {
-            let mut key = elm[0].to_string();
-            key.pop();
-            let val = elm[1].parse::<u64>().ok();
-            (key, val)
-        })
-        .collect::<HashMap<String, Option<u64>>>();
+    Some(
+        data.split('\n')
+            .map(|kv| kv.split_whitespace().take(2).collect::<Vec<&str>>())
+            .filter(|elm| !elm.is_empty())
+            .map(|elm| -> (String, Option<u64>) {
+                let mut key = elm[0].to_string();
+                key.pop();
+                let val = elm[1].parse::<u64>().ok();
+                (key, val)
+            })
+            .collect::<HashMap<String, Option<u64>>>(),
+    )
+}
+
+pub fn mem_info() -> Option<MemInfo<ByteSize>> {
+    let mem = meminfo()?;
 
     let total = mem["MemTotal"];
     let avail = mem["MemAvailable"];
@@ -156,6 +809,62 @@ pub fn mem_info() -> Option<MemInfo<ByteSize>> {
     })
 }
 
+fn zram_info(device: &str) -> Option<ZramInfo> {
+    let name = device.rsplit('/').next()?;
+    let mm_stat = read_trimmed(format!("/sys/block/{}/mm_stat", name))?;
+    let mut fields = mm_stat
+        .split_whitespace()
+        .map(|field| field.parse::<u64>().ok());
+    let orig_data = fields.next()??;
+    let compr_data = fields.next()??;
+
+    Some(ZramInfo {
+        ratio: if compr_data > 0 {
+            orig_data as f64 / compr_data as f64
+        } else {
+            0.0
+        },
+    })
+}
+
+pub fn swap_info() -> Option<SwapInfo<ByteSize>> {
+    let mem = meminfo()?;
+    let total = mem.get("SwapTotal").copied()??;
+    let free = mem.get("SwapFree").copied()??;
+
+    let swaps = fs::read_to_string("/proc/swaps").unwrap_or_default();
+    let devices = swaps
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            let name = fields.first()?.replace("\\040", " ");
+            Some(SwapDevice {
+                zram: zram_info(&name),
+                size: ByteSize::kb(fields.get(2)?.parse().ok()?),
+                used: ByteSize::kb(fields.get(3)?.parse().ok()?),
+                name,
+            })
+        })
+        .collect();
+
+    Some(SwapInfo {
+        total: ByteSize::kb(total),
+        used: ByteSize::kb(total - free),
+        devices,
+    })
+}
+
+pub fn hugepages() -> Option<HugePages<ByteSize>> {
+    let mem = meminfo()?;
+    Some(HugePages {
+        total: mem.get("HugePages_Total").copied()??,
+        free: mem.get("HugePages_Free").copied()??,
+        reserved: mem.get("HugePages_Rsvd").copied()??,
+        page_size: ByteSize::kb(mem.get("Hugepagesize").copied()??),
+    })
+}
+
 pub fn user_info() -> Option<UserInfo> {
     let user = nix::unistd::User::from_uid(nix::unistd::getuid()).ok()??;
     Some(UserInfo {
@@ -170,10 +879,52 @@ pub fn machine_info() -> MachineInfo {
     MachineInfo {
         arch: String::from(mach.machine()),
         kernel: String::from(mach.release()),
+        version: String::from(mach.version()),
         nodename: String::from(mach.nodename()),
     }
 }
 
+// Build strings end with either a date like "(2024-05-03)" or a timestamp
+// like "Thu May  2 19:54:27 UTC 2024"
+fn kernel_build_date(version: &str) -> Option<NaiveDate> {
+    if let (Some(start), Some(end)) = (version.rfind('('), version.rfind(')')) {
+        if let Some(date) = version.get(start + 1..end) {
+            if let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                return Some(date);
+            }
+        }
+    }
+
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let tokens = version.split_whitespace().collect::<Vec<&str>>();
+    let (idx, month) = tokens.iter().enumerate().find_map(|(idx, token)| {
+        let month = MONTHS.iter().position(|month| month == token)?;
+        Some((idx, month as u32 + 1))
+    })?;
+    // RFC 2822 dates put the day first: "Thu, 02 May 2024 19:54:27 +0000"
+    let rfc_day = idx
+        .checked_sub(1)
+        .and_then(|day| tokens.get(day)?.parse::<u32>().ok());
+    let (day, year) = match rfc_day {
+        Some(day) => (day, tokens.get(idx + 1)?),
+        None => (tokens.get(idx + 1)?.parse::<u32>().ok()?, tokens.last()?),
+    };
+    NaiveDate::from_ymd_opt(year.parse::<i32>().ok()?, month, day)
+}
+
+pub fn kernel_details(machine_info: &MachineInfo) -> KernelDetails {
+    KernelDetails {
+        cmdline: read_trimmed("/proc/cmdline"),
+        taint: read_num::<u64, _>("/proc/sys/kernel/tainted"),
+        modules: fs::read_to_string("/proc/modules")
+            .ok()
+            .map(|modules| modules.lines().count()),
+        build_date: kernel_build_date(&machine_info.version),
+    }
+}
+
 pub fn color_scheme() -> Vec<Color> {
     (40..=47)
         .chain(100..=107)
@@ -197,7 +948,7 @@ pub fn distro() -> Option<Distro> {
 
     Some(Distro {
         name: os_release["NAME"].clone(),
-        color: os_release["ANSI_COLOR"].clone(),
+        color: os_release.get("ANSI_COLOR").cloned().unwrap_or_default(),
     })
 }
 
@@ -206,6 +957,7 @@ pub fn sysinfo() -> Option<SysInfo> {
     Some(SysInfo {
         uptime: sinf.uptime(),
         process_num: sinf.process_count(),
+        load_avg: sinf.load_average(),
     })
 }
 
@@ -234,44 +986,1810 @@ pub fn disk_usage(path: &str) -> Option<FsInfo<ByteSize>> {
     })
 }
 
-pub fn device() -> Option<DeviceInfo> {
-    let data = fs::read_to_string("/sys/class/dmi/id/product_name").ok()?;
-    Some(DeviceInfo(data.trim().to_string()))
+// Mount table fields escape whitespace as octal, e.g. "\040" for a space
+fn unescape_mount_field(field: &str) -> String {
+    field
+        .replace("\\040", " ")
+        .replace("\\011", "\t")
+        .replace("\\012", "\n")
+        .replace("\\134", "\\")
 }
 
-pub fn get_temp() -> Option<Temp> {
-    let data = fs::read_to_string("/sys/class/thermal/thermal_zone0/temp").ok()?;
-    Some(Temp(data.trim().parse::<i32>().ok()?))
+pub fn mounts() -> Option<Vec<Mount>> {
+    let data = fs::read_to_string("/proc/self/mounts").ok()?;
+    Some(
+        data.lines()
+            .filter_map(|line| {
+                // The source device comes first and is not needed
+                let mut fields = line.split_whitespace().skip(1);
+                Some(Mount {
+                    mount_point: unescape_mount_field(fields.next()?),
+                    fs_type: fields.next()?.to_string(),
+                })
+            })
+            .collect(),
+    )
 }
 
-pub fn ip(iptype: IpType) -> Option<Ipv4Addr> {
-    match iptype {
-        IpType::Public => {
-            let response = minreq::get("http://ifconfig.me").send();
-            let res = response.ok()?;
-            let res_str = res.as_str().ok()?;
-            return res_str.parse::<Ipv4Addr>().ok();
-        }
-        IpType::Private => {
-            let addrs = nix::ifaddrs::getifaddrs().ok()?;
-
-            for ifaddr in addrs {
-                if let Some(x) = ifaddr.address {
-                    if let nix::sys::socket::AddressFamily::Inet = x.family() {
-                        let x = x.to_string();
-                        let addr = x.split(':').next()?.parse::<Ipv4Addr>().ok()?;
-                        if addr.is_private() {
-                            return Some(addr);
-                        }
-                    }
-                }
+// Mounts backed by a block device, leaving out pseudo filesystems (the ones
+// flagged "nodev" in /proc/filesystems) and read-only images. Mounts are
+// told apart by the device number of the mount point rather than the source
+// device, since btrfs subvolumes share a source but each get their own number,
+// while a directory mounted twice keeps the same one
+pub fn real_mounts() -> Option<Vec<Mount>> {
+    let filesystems = fs::read_to_string("/proc/filesystems").ok()?;
+    let block_fs = filesystems
+        .lines()
+        .filter(|line| !line.starts_with("nodev"))
+        .map(|line| line.trim())
+        .filter(|fs_type| !matches!(*fs_type, "squashfs" | "erofs" | "iso9660"))
+        .collect::<HashSet<&str>>();
+
+    let mut seen = HashSet::new();
+    Some(
+        mounts()?
+            .into_iter()
+            .filter(|mount| block_fs.contains(mount.fs_type.as_str()))
+            .filter(|mount| match fs::metadata(&mount.mount_point) {
+                Ok(meta) => seen.insert(meta.dev()),
+                Err(_) => true,
+            })
+            .collect(),
+    )
+}
+
+pub fn block_devices() -> Option<Vec<BlockDevice<ByteSize>>> {
+    let mut devices = fs::read_dir("/sys/block")
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        // Only physical disks have a backing device, this skips loop, ram,
+        // zram and device mapper nodes
+        .filter(|path| path.join("device").exists())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_string();
+            let model = read_trimmed(path.join("device/model"))
+                .or_else(|| read_trimmed(path.join("device/name")))
+                .filter(|model| !model.is_empty());
+            // The size is always reported in 512 byte sectors
+            let size = read_num::<u64, _>(path.join("size"))? * 512;
+            let kind = if name.starts_with("nvme") {
+                DiskKind::Nvme
+            } else if read_trimmed(path.join("queue/rotational")).as_deref() == Some("1") {
+                DiskKind::Hdd
+            } else {
+                DiskKind::Ssd
+            };
+            let removable = read_trimmed(path.join("removable")).as_deref() == Some("1");
+
+            Some(BlockDevice {
+                name,
+                model,
+                size: ByteSize::b(size),
+                kind,
+                removable,
+            })
+        })
+        .filter(|device| device.size.as_u64() > 0)
+        .collect::<Vec<BlockDevice<ByteSize>>>();
+
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(devices)
+}
+
+// Fields of the EDID base block relevant for identifying a monitor, see
+// VESA E-EDID 1.4 section 3
+struct Edid {
+    manufacturer: String,
+    model: Option<String>,
+    size_mm: Option<(u32, u32)>,
+    // Resolution and refresh rate of the preferred detailed timing
+    preferred: Option<(u32, u32, f64)>,
+    max_refresh: Option<u32>,
+}
+
+fn parse_edid(edid: &[u8]) -> Option<Edid> {
+    if edid.len() < 128 || edid[0..8] != [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00] {
+        return None;
+    }
+    // Three 5 bit letters, 1 being 'A'
+    let id = u16::from_be_bytes([edid[8], edid[9]]);
+    let manufacturer = [(id >> 10) & 0x1f, (id >> 5) & 0x1f, id & 0x1f]
+        .iter()
+        .map(|letter| (b'A' - 1 + *letter as u8) as char)
+        .collect::<String>();
+
+    let mut edid_info = Edid {
+        manufacturer,
+        model: None,
+        // Fallback in centimetres, overridden by the detailed timing
+        size_mm: Some((edid[21] as u32 * 10, edid[22] as u32 * 10))
+            .filter(|(width, height)| *width > 0 && *height > 0),
+        preferred: None,
+        max_refresh: None,
+    };
+
+    for desc in edid[54..126].chunks(18) {
+        if desc[0] != 0 || desc[1] != 0 {
+            if edid_info.preferred.is_some() {
+                continue;
+            }
+            // Detailed timing descriptor, the first one is the preferred mode
+            let clock = u16::from_le_bytes([desc[0], desc[1]]) as f64 * 10_000.0;
+            let h_active = desc[2] as u32 | ((desc[4] as u32 & 0xf0) << 4);
+            let h_blank = desc[3] as u32 | ((desc[4] as u32 & 0x0f) << 8);
+            let v_active = desc[5] as u32 | ((desc[7] as u32 & 0xf0) << 4);
+            let v_blank = desc[6] as u32 | ((desc[7] as u32 & 0x0f) << 8);
+            let total = ((h_active + h_blank) * (v_active + v_blank)) as f64;
+            if total > 0.0 {
+                edid_info.preferred = Some((h_active, v_active, clock / total));
+            }
+            let width = desc[12] as u32 | ((desc[14] as u32 & 0xf0) << 4);
+            let height = desc[13] as u32 | ((desc[14] as u32 & 0x0f) << 8);
+            if width > 0 && height > 0 {
+                edid_info.size_mm = Some((width, height));
+            }
+            continue;
+        }
+        match desc[3] {
+            // Monitor name, terminated by a newline
+            0xfc => {
+                let name = desc[5..18]
+                    .iter()
+                    .take_while(|c| **c != 0x0a)
+                    .map(|c| *c as char)
+                    .collect::<String>();
+                edid_info.model = Some(name.trim().to_string()).filter(|name| !name.is_empty());
             }
+            // Range limits, byte 6 is the maximum vertical rate in Hz
+            0xfd => edid_info.max_refresh = Some(desc[6] as u32).filter(|rate| *rate > 0),
+            _ => {}
         }
     }
-    None
+    Some(edid_info)
 }
 
-pub fn packages(distro: &str) -> Option<usize> {
+// Minimal KMS client, just enough to read the mode each connector is
+// currently driven at. These ioctls don't need DRM master, so any user
+// with access to the card node can issue them.
+mod kms {
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::os::unix::io::AsRawFd;
+
+    // Indexed by DRM_MODE_CONNECTOR_*, named the way sysfs names connectors
+    const CONNECTOR_TYPES: [&str; 21] = [
+        "Unknown",
+        "VGA",
+        "DVI-I",
+        "DVI-D",
+        "DVI-A",
+        "Composite",
+        "SVIDEO",
+        "LVDS",
+        "Component",
+        "DIN",
+        "DP",
+        "HDMI-A",
+        "HDMI-B",
+        "TV",
+        "eDP",
+        "Virtual",
+        "DSI",
+        "DPI",
+        "Writeback",
+        "SPI",
+        "USB",
+    ];
+    const DRM_MODE_FLAG_INTERLACE: u32 = 1 << 4;
+    const DRM_MODE_FLAG_DBLSCAN: u32 = 1 << 5;
+
+    // Mirrors of the structs in include/uapi/drm/drm_mode.h
+    #[repr(C)]
+    #[derive(Default)]
+    struct CardRes {
+        fb_id_ptr: u64,
+        crtc_id_ptr: u64,
+        connector_id_ptr: u64,
+        encoder_id_ptr: u64,
+        count_fbs: u32,
+        count_crtcs: u32,
+        count_connectors: u32,
+        count_encoders: u32,
+        min_width: u32,
+        max_width: u32,
+        min_height: u32,
+        max_height: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct ModeInfo {
+        clock: u32,
+        hdisplay: u16,
+        hsync_start: u16,
+        hsync_end: u16,
+        htotal: u16,
+        hskew: u16,
+        vdisplay: u16,
+        vsync_start: u16,
+        vsync_end: u16,
+        vtotal: u16,
+        vscan: u16,
+        vrefresh: u32,
+        flags: u32,
+        kind: u32,
+        name: [u8; 32],
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct Crtc {
+        set_connectors_ptr: u64,
+        count_connectors: u32,
+        crtc_id: u32,
+        fb_id: u32,
+        x: u32,
+        y: u32,
+        gamma_size: u32,
+        mode_valid: u32,
+        mode: ModeInfo,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct Encoder {
+        encoder_id: u32,
+        encoder_type: u32,
+        crtc_id: u32,
+        possible_crtcs: u32,
+        possible_clones: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct Connector {
+        encoders_ptr: u64,
+        modes_ptr: u64,
+        props_ptr: u64,
+        prop_values_ptr: u64,
+        count_modes: u32,
+        count_props: u32,
+        count_encoders: u32,
+        encoder_id: u32,
+        connector_id: u32,
+        connector_type: u32,
+        connector_type_id: u32,
+        connection: u32,
+        mm_width: u32,
+        mm_height: u32,
+        subpixel: u32,
+        pad: u32,
+    }
+
+    nix::ioctl_readwrite!(get_resources, b'd', 0xA0, CardRes);
+    nix::ioctl_readwrite!(get_crtc, b'd', 0xA1, Crtc);
+    nix::ioctl_readwrite!(get_encoder, b'd', 0xA6, Encoder);
+    nix::ioctl_readwrite!(get_connector, b'd', 0xA7, Connector);
+
+    fn refresh(mode: &ModeInfo) -> f64 {
+        let pixels = mode.htotal as f64 * mode.vtotal as f64;
+        if pixels == 0.0 {
+            return mode.vrefresh as f64;
+        }
+        // The clock is in kHz
+        let mut refresh = mode.clock as f64 * 1000.0 / pixels;
+        if mode.flags & DRM_MODE_FLAG_INTERLACE != 0 {
+            refresh *= 2.0;
+        }
+        if mode.flags & DRM_MODE_FLAG_DBLSCAN != 0 {
+            refresh /= 2.0;
+        }
+        if mode.vscan > 1 {
+            refresh /= mode.vscan as f64;
+        }
+        refresh
+    }
+
+    // Active width, height and refresh rate of a connector, following it
+    // to its encoder and from there to the CRTC scanning it out
+    fn active_mode(fd: i32, connector_id: u32) -> Option<(String, (u32, u32, f64))> {
+        let mut connector = Connector {
+            connector_id,
+            ..Default::default()
+        };
+        unsafe { get_connector(fd, &mut connector) }.ok()?;
+        let name = format!(
+            "{}-{}",
+            CONNECTOR_TYPES.get(connector.connector_type as usize)?,
+            connector.connector_type_id
+        );
+        if connector.encoder_id == 0 {
+            return None;
+        }
+
+        let mut encoder = Encoder {
+            encoder_id: connector.encoder_id,
+            ..Default::default()
+        };
+        unsafe { get_encoder(fd, &mut encoder) }.ok()?;
+        if encoder.crtc_id == 0 {
+            return None;
+        }
+
+        let mut crtc = Crtc {
+            crtc_id: encoder.crtc_id,
+            ..Default::default()
+        };
+        unsafe { get_crtc(fd, &mut crtc) }.ok()?;
+        if crtc.mode_valid == 0 {
+            return None;
+        }
+        let mode = &crtc.mode;
+        Some((
+            name,
+            (mode.hdisplay as u32, mode.vdisplay as u32, refresh(mode)),
+        ))
+    }
+
+    // Current modes of a card's lit connectors, keyed by connector name
+    pub fn current_modes(card: &str) -> HashMap<String, (u32, u32, f64)> {
+        let file = match File::open(format!("/dev/dri/{}", card)) {
+            Ok(file) => file,
+            Err(_) => return HashMap::new(),
+        };
+        let fd = file.as_raw_fd();
+
+        // The first call only reports how many connectors there are
+        let mut res = CardRes::default();
+        if unsafe { get_resources(fd, &mut res) }.is_err() {
+            return HashMap::new();
+        }
+        let mut ids = vec![0u32; res.count_connectors as usize];
+        let mut res = CardRes {
+            connector_id_ptr: ids.as_mut_ptr() as u64,
+            count_connectors: ids.len() as u32,
+            ..Default::default()
+        };
+        if unsafe { get_resources(fd, &mut res) }.is_err() {
+            return HashMap::new();
+        }
+        // Connectors can disappear between the two calls
+        ids.truncate(res.count_connectors as usize);
+
+        ids.into_iter()
+            .filter_map(|id| active_mode(fd, id))
+            .collect()
+    }
+}
+
+pub fn monitors() -> Option<Vec<Monitor>> {
+    let mut connectors = fs::read_dir("/sys/class/drm")
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        // Connectors are named like card0-eDP-1
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("card") && name.contains('-'))
+        })
+        .filter(|path| read_trimmed(path.join("status")).as_deref() == Some("connected"))
+        .collect::<Vec<PathBuf>>();
+    connectors.sort();
+
+    let mut current_modes = HashMap::new();
+    Some(
+        connectors
+            .into_iter()
+            .filter_map(|path| {
+                let name = path.file_name()?.to_str()?;
+                let (card, connector) = name.split_once('-')?;
+                let current = current_modes
+                    .entry(card.to_string())
+                    .or_insert_with(|| kms::current_modes(card))
+                    .get(connector)
+                    .copied();
+                let connector = connector.to_string();
+                let edid = fs::read(path.join("edid"))
+                    .ok()
+                    .and_then(|edid| parse_edid(&edid));
+                // Both values come from the EDID preferred timing, without
+                // one the kernel still lists the preferred mode first
+                let preferred = edid.as_ref().and_then(|edid| edid.preferred);
+                let native_resolution = match preferred {
+                    Some((width, height, _)) => Some(format!("{}x{}", width, height)),
+                    None => read_trimmed(path.join("modes"))
+                        .and_then(|modes| modes.lines().next().map(String::from)),
+                };
+
+                Some(Monitor {
+                    connector,
+                    manufacturer: edid.as_ref().map(|edid| edid.manufacturer.clone()),
+                    model: edid.as_ref().and_then(|edid| edid.model.clone()),
+                    resolution: current.map(|(width, height, _)| format!("{}x{}", width, height)),
+                    refresh: current.map(|(_, _, refresh)| refresh),
+                    native_resolution,
+                    native_refresh: preferred.map(|(_, _, refresh)| refresh),
+                    max_refresh: edid.as_ref().and_then(|edid| edid.max_refresh),
+                    size_mm: edid.as_ref().and_then(|edid| edid.size_mm),
+                })
+            })
+            .collect(),
+    )
+}
+
+// Flattens an ini file into "section/key" entries
+fn read_ini(path: &Path) -> HashMap<String, String> {
+    let data = fs::read_to_string(path).unwrap_or_default();
+    let mut section = String::new();
+    let mut entries = HashMap::new();
+    for line in data.lines().map(str::trim) {
+        if line.starts_with('[') && line.ends_with(']') {
+            section = line[1..line.len() - 1].to_string();
+        } else if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim().trim_matches('"').to_string();
+            if !value.is_empty() {
+                entries.insert(format!("{}/{}", section, key.trim()), value);
+            }
+        }
+    }
+    entries
+}
+
+// QFont strings look like "Noto Sans,10,-1,5,50,0,0,0,0,0"
+fn qt_font(font: &str) -> String {
+    let mut fields = font.split(',');
+    match (fields.next(), fields.next()) {
+        (Some(family), Some(size)) => format!("{} {}", family, size),
+        _ => font.to_string(),
+    }
+}
+
+pub fn desktop_theme(home: &Path) -> DesktopTheme {
+    let config = get_env("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".config"));
+
+    // GTK 2 uses the same keys as the GTK 3 and 4 ini files, just without
+    // a section, so read_ini files them under "/key"
+    let gtk = [
+        (config.join("gtk-3.0/settings.ini"), "Settings/"),
+        (config.join("gtk-4.0/settings.ini"), "Settings/"),
+        (home.join(".gtkrc-2.0"), "/"),
+    ]
+    .iter()
+    .map(|(path, prefix)| (read_ini(path), *prefix))
+    .collect::<Vec<(HashMap<String, String>, &str)>>();
+    let gtk_setting = |key: &str| {
+        gtk.iter()
+            .find_map(|(ini, prefix)| ini.get(&format!("{}{}", prefix, key)).cloned())
+    };
+
+    // Plasma keeps its settings in kdeglobals, other desktops use qt5ct/qt6ct
+    let kdeglobals = read_ini(&config.join("kdeglobals"));
+    let kcminputrc = read_ini(&config.join("kcminputrc"));
+    let qtct = [
+        config.join("qt6ct/qt6ct.conf"),
+        config.join("qt5ct/qt5ct.conf"),
+    ]
+    .iter()
+    .map(|path| read_ini(path))
+    .find(|ini| !ini.is_empty())
+    .unwrap_or_default();
+
+    // The default cursor is inherited from ~/.icons/default when not set
+    let default_cursor = read_ini(&home.join(".icons/default/index.theme"))
+        .get("Icon Theme/Inherits")
+        .cloned();
+
+    DesktopTheme {
+        theme: ToolkitSetting {
+            gtk: gtk_setting("gtk-theme-name"),
+            // KDE 4 kept the style under [General], Plasma moved it to [KDE]
+            qt: kdeglobals
+                .get("KDE/widgetStyle")
+                .or_else(|| kdeglobals.get("General/widgetStyle"))
+                .or_else(|| qtct.get("Appearance/style"))
+                .cloned(),
+        },
+        icons: ToolkitSetting {
+            gtk: gtk_setting("gtk-icon-theme-name"),
+            qt: kdeglobals
+                .get("Icons/Theme")
+                .or_else(|| qtct.get("Appearance/icon_theme"))
+                .cloned(),
+        },
+        cursor: ToolkitSetting {
+            gtk: gtk_setting("gtk-cursor-theme-name").or_else(|| default_cursor.clone()),
+            qt: kcminputrc
+                .get("Mouse/cursorTheme")
+                .cloned()
+                .or(default_cursor),
+        },
+        font: ToolkitSetting {
+            gtk: gtk_setting("gtk-font-name"),
+            qt: kdeglobals
+                .get("General/font")
+                // Older qt5ct versions store a serialized QVariant instead
+                .or_else(|| {
+                    qtct.get("Fonts/general")
+                        .filter(|font| !font.starts_with("@Variant("))
+                })
+                .map(|font| qt_font(font)),
+        },
+    }
+}
+
+// Values vendors leave in DMI fields they didn't bother to fill in
+const DMI_PLACEHOLDERS: [&str; 12] = [
+    "to be filled by o.e.m.",
+    "default string",
+    "system product name",
+    "system manufacturer",
+    "system version",
+    "not applicable",
+    "not specified",
+    "type1productconfigid",
+    "0123456789",
+    "none",
+    "oem",
+    "x.x",
+];
+
+fn dmi(key: &str) -> Option<String> {
+    read_trimmed(Path::new("/sys/class/dmi/id").join(key)).filter(|value| {
+        !value.is_empty() && !DMI_PLACEHOLDERS.contains(&value.to_lowercase().as_str())
+    })
+}
+
+pub fn device() -> Option<DeviceInfo> {
+    let name = dmi("product_name").or_else(|| dmi("board_name"))?;
+    Some(DeviceInfo(name))
+}
+
+pub fn board() -> Option<BoardInfo> {
+    // SMBIOS system enclosure types, see DSP0134 section 7.4.1
+    let chassis = read_num::<u8, _>("/sys/class/dmi/id/chassis_type").map(|kind| match kind {
+        3..=7 | 13 | 15 | 16 | 24 | 35 | 36 => ChassisType::Desktop,
+        8..=10 | 14 | 31 | 32 => ChassisType::Laptop,
+        11 | 30 => ChassisType::Tablet,
+        17 | 23 | 25 | 28 | 29 => ChassisType::Server,
+        _ => ChassisType::Other,
+    });
+    let board = BoardInfo {
+        vendor: dmi("board_vendor").or_else(|| dmi("sys_vendor")),
+        name: dmi("board_name"),
+        version: dmi("board_version").or_else(|| dmi("product_version")),
+        chassis,
+    };
+    if board.vendor.is_none() && board.name.is_none() {
+        return None;
+    }
+    Some(board)
+}
+
+fn detect_hypervisor() -> Option<String> {
+    let sys_vendor = dmi("sys_vendor").unwrap_or_default();
+    let product = dmi("product_name").unwrap_or_default();
+    let known = [
+        ("KVM", "KVM"),
+        ("QEMU", "QEMU"),
+        ("VMware", "VMware"),
+        ("VirtualBox", "VirtualBox"),
+        ("innotek", "VirtualBox"),
+        ("Xen", "Xen"),
+        ("Parallels", "Parallels"),
+        ("Bochs", "Bochs"),
+    ];
+    if let Some((_, name)) = known
+        .iter()
+        .find(|(needle, _)| product.contains(needle) || sys_vendor.contains(needle))
+    {
+        return Some(name.to_string());
+    }
+    if sys_vendor == "Microsoft Corporation" && product == "Virtual Machine" {
+        return Some(String::from("Hyper-V"));
+    }
+    if let Some(kind) = read_trimmed("/sys/hypervisor/type") {
+        return Some(match kind.as_str() {
+            "xen" => String::from("Xen"),
+            _ => kind,
+        });
+    }
+
+    // The CPU flag is set by every hypervisor, even when DMI is hidden
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok()?;
+    let flags = cpuinfo.lines().find(|line| line.starts_with("flags"))?;
+    if flags.split_whitespace().any(|flag| flag == "hypervisor") {
+        return Some(String::from("unknown hypervisor"));
+    }
+    None
+}
+
+fn detect_container() -> Option<String> {
+    if Path::new("/.dockerenv").exists() {
+        return Some(String::from("Docker"));
+    }
+    if Path::new("/run/.containerenv").exists() {
+        return Some(String::from("Podman"));
+    }
+    // Written by systemd-nspawn, LXC and other container managers
+    if let Some(container) = read_trimmed("/run/systemd/container") {
+        return Some(match container.as_str() {
+            "systemd-nspawn" => String::from("systemd-nspawn"),
+            "lxc" | "lxc-libvirt" => String::from("LXC"),
+            "docker" => String::from("Docker"),
+            "podman" => String::from("Podman"),
+            _ => container,
+        });
+    }
+    let cgroup = fs::read_to_string("/proc/1/cgroup").unwrap_or_default();
+    let known = [
+        ("kubepods", "Kubernetes"),
+        ("docker", "Docker"),
+        ("libpod", "Podman"),
+        ("lxc", "LXC"),
+        ("machine.slice", "systemd-nspawn"),
+    ];
+    if let Some((_, name)) = known.iter().find(|(needle, _)| cgroup.contains(needle)) {
+        return Some(name.to_string());
+    }
+    let osrelease = read_trimmed("/proc/sys/kernel/osrelease")?.to_lowercase();
+    if osrelease.contains("microsoft") || osrelease.contains("wsl") {
+        return Some(String::from("WSL"));
+    }
+    None
+}
+
+pub fn virt() -> VirtInfo {
+    VirtInfo {
+        hypervisor: detect_hypervisor(),
+        container: detect_container(),
+    }
+}
+
+// First line of a "<program> --version" call
+fn command_version(program: &str) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .arg("--version")
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    Some(stdout.lines().next()?.trim().to_string()).filter(|line| !line.is_empty())
+}
+
+const EFI_GLOBAL_GUID: &str = "8be4df61-93ca-11d2-aa0d-00e098032b8c";
+// Vendor GUID of the Boot Loader Interface variables, see
+// https://systemd.io/BOOT_LOADER_INTERFACE/
+const EFI_LOADER_GUID: &str = "4a67b082-0a4c-41cf-b6c7-440b29bb8c4f";
+
+// Reads an EFI variable, skipping the 4 byte attribute header
+fn efivar(name: &str, guid: &str) -> Option<Vec<u8>> {
+    let path = format!("/sys/firmware/efi/efivars/{}-{}", name, guid);
+    let data = fs::read(path).ok()?;
+    Some(data.get(4..)?.to_vec())
+}
+
+pub fn boot_info() -> BootInfo {
+    let boot_time = fs::read_to_string("/proc/stat").ok().and_then(|stat| {
+        let btime = stat
+            .lines()
+            .find_map(|line| line.strip_prefix("btime "))?
+            .trim()
+            .parse::<i64>()
+            .ok()?;
+        Local.timestamp_opt(btime, 0).single()
+    });
+    let uefi = Path::new("/sys/firmware/efi").exists();
+    let secure_boot = efivar("SecureBoot", EFI_GLOBAL_GUID)
+        .and_then(|value| value.first().copied())
+        .map(|enabled| enabled == 1);
+
+    // LoaderInfo is a NUL terminated UTF-16 string like "systemd-boot 252"
+    let bootloader = efivar("LoaderInfo", EFI_LOADER_GUID)
+        .map(|value| {
+            let utf16 = value
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .take_while(|c| *c != 0)
+                .collect::<Vec<u16>>();
+            String::from_utf16_lossy(&utf16)
+        })
+        .filter(|loader| !loader.is_empty())
+        .or_else(|| {
+            ["/boot/grub/grub.cfg", "/boot/grub2/grub.cfg"]
+                .iter()
+                .any(|path| Path::new(path).exists())
+                .then(|| String::from("GRUB"))
+        });
+
+    BootInfo {
+        boot_time,
+        uefi,
+        secure_boot,
+        bootloader,
+    }
+}
+
+pub fn security() -> SecurityInfo {
+    let lsms = read_trimmed("/sys/kernel/security/lsm")
+        .map(|lsms| {
+            lsms.split(',')
+                .filter(|lsm| !lsm.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+    let selinux = read_trimmed("/sys/fs/selinux/enforce").map(|enforce| {
+        String::from(if enforce == "1" {
+            "enforcing"
+        } else {
+            "permissive"
+        })
+    });
+    let apparmor = read_trimmed("/sys/module/apparmor/parameters/enabled")
+        .filter(|enabled| enabled == "Y")
+        .map(|_| {
+            fs::read_to_string("/sys/kernel/security/apparmor/profiles")
+                .ok()
+                .map(|profiles| profiles.lines().count())
+        });
+    // "none [integrity] confidentiality", the active mode is bracketed
+    let lockdown = read_trimmed("/sys/kernel/security/lockdown").and_then(|lockdown| {
+        let start = lockdown.find('[')?;
+        let end = lockdown.find(']')?;
+        Some(lockdown.get(start + 1..end)?.to_string())
+    });
+
+    let mut vulnerabilities = VulnSummary::default();
+    let mut entries = fs::read_dir("/sys/devices/system/cpu/vulnerabilities")
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .collect::<Vec<PathBuf>>()
+        })
+        .unwrap_or_default();
+    entries.sort();
+    for entry in entries {
+        let status = read_trimmed(&entry).unwrap_or_default();
+        if status.starts_with("Not affected") {
+            vulnerabilities.not_affected += 1;
+        } else if status.starts_with("Vulnerable") {
+            let name = entry.file_name().unwrap_or_default().to_string_lossy();
+            vulnerabilities.vulnerable.push(name.to_string());
+        } else if status.starts_with("Mitigation:") {
+            vulnerabilities.mitigated += 1;
+        } else {
+            vulnerabilities.unknown += 1;
+        }
+    }
+
+    SecurityInfo {
+        lsms,
+        selinux,
+        apparmor,
+        lockdown,
+        vulnerabilities,
+    }
+}
+
+// Layout of glibc's struct utmp on Linux, see utmp(5)
+const UTMP_SIZE: usize = 384;
+const UTMP_USER_PROCESS: i16 = 7;
+
+fn utmp_str(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).to_string()
+}
+
+fn parse_utmp(data: &[u8]) -> Vec<UtmpEntry> {
+    data.chunks_exact(UTMP_SIZE)
+        .filter_map(|record| {
+            let time = i32::from_ne_bytes(record[340..344].try_into().ok()?);
+            Some(UtmpEntry {
+                kind: i16::from_ne_bytes(record[0..2].try_into().ok()?),
+                pid: i32::from_ne_bytes(record[4..8].try_into().ok()?),
+                line: utmp_str(&record[8..40]),
+                user: utmp_str(&record[44..76]),
+                host: utmp_str(&record[76..332]),
+                time: Local.timestamp_opt(time as i64, 0).single()?,
+            })
+        })
+        .collect()
+}
+
+fn read_utmp(path: &str) -> Option<Vec<UtmpEntry>> {
+    Some(parse_utmp(&fs::read(path).ok()?))
+}
+
+pub fn sessions(user: &str) -> Option<SessionInfo> {
+    // Entries of crashed sessions can linger, so check the process is alive
+    let sessions = read_utmp("/var/run/utmp")?
+        .into_iter()
+        .filter(|entry| entry.kind == UTMP_USER_PROCESS)
+        .filter(|entry| Path::new(&format!("/proc/{}", entry.pid)).exists())
+        .collect::<Vec<UtmpEntry>>();
+    let mut users = sessions
+        .iter()
+        .map(|entry| entry.user.clone())
+        .collect::<Vec<String>>();
+    users.sort();
+    users.dedup();
+
+    // The newest wtmp login of a logged in user is the current session
+    let mut logins = read_utmp("/var/log/wtmp")
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entry| entry.kind == UTMP_USER_PROCESS && entry.user == user)
+        .collect::<Vec<UtmpEntry>>();
+    if users.iter().any(|name| name == user) {
+        logins.pop();
+    }
+
+    Some(SessionInfo {
+        sessions: sessions.len(),
+        users,
+        last_login: logins.pop(),
+    })
+}
+
+struct ProcessSample {
+    pid: u32,
+    name: String,
+    state: char,
+    rss_kb: u64,
+    cpu_ticks: u64,
+}
+
+fn process_samples() -> Vec<ProcessSample> {
+    let procs = match fs::read_dir("/proc") {
+        Ok(procs) => procs,
+        Err(_) => return Vec::new(),
+    };
+    procs
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let pid = entry.file_name().to_str()?.parse::<u32>().ok()?;
+            let stat = fs::read_to_string(entry.path().join("stat")).ok()?;
+            // The name is in parentheses and may itself contain spaces or
+            // parentheses, the remaining fields start after the last ')'
+            let name = stat.get(stat.find('(')? + 1..stat.rfind(')')?)?.to_string();
+            let fields = stat[stat.rfind(')')? + 1..]
+                .split_whitespace()
+                .collect::<Vec<&str>>();
+            let utime = fields.get(11)?.parse::<u64>().ok()?;
+            let stime = fields.get(12)?.parse::<u64>().ok()?;
+            // Kernel threads have no VmRSS line
+            let status = fs::read_to_string(entry.path().join("status")).unwrap_or_default();
+            let rss_kb = status
+                .lines()
+                .find_map(|line| line.strip_prefix("VmRSS:"))
+                .and_then(|rss| rss.split_whitespace().next()?.parse::<u64>().ok())
+                .unwrap_or(0);
+            Some(ProcessSample {
+                pid,
+                name,
+                state: fields.first()?.chars().next()?,
+                rss_kb,
+                cpu_ticks: utime + stime,
+            })
+        })
+        .collect()
+}
+
+pub fn process_stats(count: usize, interval: Duration) -> ProcessStats<ByteSize> {
+    let before = process_samples()
+        .into_iter()
+        .map(|sample| (sample.pid, sample.cpu_ticks))
+        .collect::<HashMap<u32, u64>>();
+    std::thread::sleep(interval);
+    let samples = process_samples();
+
+    let ticks_per_sec = nix::unistd::sysconf(nix::unistd::SysconfVar::CLK_TCK)
+        .ok()
+        .flatten()
+        .unwrap_or(100) as f64;
+    let secs = interval.as_secs_f64();
+    let mut usage = samples
+        .iter()
+        .map(|sample| {
+            // Processes started during the interval count from zero
+            let ticks = sample
+                .cpu_ticks
+                .saturating_sub(before.get(&sample.pid).copied().unwrap_or(0));
+            ProcessUsage {
+                pid: sample.pid,
+                name: sample.name.clone(),
+                rss: ByteSize::kb(sample.rss_kb),
+                cpu: if secs > 0.0 {
+                    ticks as f64 / ticks_per_sec / secs * 100.0
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect::<Vec<ProcessUsage<ByteSize>>>();
+
+    let mut by_mem = usage.clone();
+    by_mem.sort_by_key(|proc| std::cmp::Reverse(proc.rss));
+    by_mem.truncate(count);
+    usage.sort_by(|a, b| {
+        b.cpu
+            .partial_cmp(&a.cpu)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    usage.truncate(count);
+
+    ProcessStats {
+        total: samples.len(),
+        running: samples.iter().filter(|sample| sample.state == 'R').count(),
+        zombies: samples.iter().filter(|sample| sample.state == 'Z').count(),
+        by_mem,
+        by_cpu: usage,
+    }
+}
+
+// Name of the process running as PID 1
+pub fn init_comm() -> Option<String> {
+    read_trimmed("/proc/1/comm")
+}
+
+pub fn init_system() -> Option<InitInfo> {
+    let comm = init_comm()?;
+    let (name, version) = match comm.as_str() {
+        // "systemd 252 (252.6-1)"
+        "systemd" => (
+            "systemd",
+            command_version("systemctl")
+                .and_then(|version| version.split_whitespace().nth(1).map(String::from)),
+        ),
+        "runit" | "runit-init" => ("runit", None),
+        "s6-svscan" => ("s6", None),
+        // "dinit version 0.17.0."
+        "dinit" => (
+            "dinit",
+            command_version("dinit").and_then(|version| {
+                Some(
+                    version
+                        .split_whitespace()
+                        .last()?
+                        .trim_end_matches('.')
+                        .to_string(),
+                )
+            }),
+        ),
+        // OpenRC runs on top of sysvinit or busybox init
+        "init" if Path::new("/run/openrc").exists() => (
+            "OpenRC",
+            command_version("openrc")
+                .and_then(|version| version.split_whitespace().last().map(String::from)),
+        ),
+        "init" => ("SysVinit", None),
+        _ => {
+            return Some(InitInfo {
+                name: comm,
+                version: None,
+            })
+        }
+    };
+    Some(InitInfo {
+        name: name.to_string(),
+        version,
+    })
+}
+
+pub fn services(manager: &dyn ServiceManager) -> Option<ServiceStatus> {
+    Some(ServiceStatus {
+        running: manager.running_units()?.len(),
+        failed: manager.failed_units()?,
+    })
+}
+
+pub fn bios() -> Option<BiosInfo> {
+    // Dates are stored as MM/DD/YYYY
+    let date = dmi("bios_date").map(|date| {
+        let parts = date.split('/').collect::<Vec<&str>>();
+        match parts.as_slice() {
+            [month, day, year] => format!("{}-{}-{}", year, month, day),
+            _ => date.clone(),
+        }
+    });
+    let bios = BiosInfo {
+        vendor: dmi("bios_vendor"),
+        version: dmi("bios_version"),
+        date,
+    };
+    if bios.vendor.is_none() && bios.version.is_none() {
+        return None;
+    }
+    Some(bios)
+}
+
+// hwmon drivers which report the CPU package temperature
+const CPU_TEMP_CHIPS: [&str; 4] = ["coretemp", "k10temp", "zenpower", "cpu_thermal"];
+
+// Lists the "<prefix>N" channels of a hwmon device, e.g. temp1 and temp2
+fn hwmon_channels(hwmon: &Path, prefix: &str) -> Vec<String> {
+    let mut channels = fs::read_dir(hwmon)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter_map(|name| {
+                    let channel = name.strip_suffix("_input")?;
+                    let id = channel.strip_prefix(prefix)?;
+                    id.parse::<u32>().ok()?;
+                    Some(channel.to_string())
+                })
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_default();
+    channels.sort_by_key(|channel| channel[prefix.len()..].parse::<u32>().unwrap_or(0));
+    channels
+}
+
+fn hwmon_devices() -> Vec<PathBuf> {
+    let mut devices = fs::read_dir("/sys/class/hwmon")
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .collect::<Vec<PathBuf>>()
+        })
+        .unwrap_or_default();
+    devices.sort();
+    devices
+}
+
+pub fn temp_sensors() -> Vec<TempSensor> {
+    let mut sensors = Vec::new();
+    for hwmon in hwmon_devices() {
+        let chip = match read_trimmed(hwmon.join("name")) {
+            Some(chip) => chip,
+            None => continue,
+        };
+        for channel in hwmon_channels(&hwmon, "temp") {
+            let millidegrees = match read_num::<f64, _>(hwmon.join(format!("{}_input", channel))) {
+                Some(millidegrees) => millidegrees,
+                None => continue,
+            };
+            sensors.push(TempSensor {
+                chip: chip.clone(),
+                label: read_trimmed(hwmon.join(format!("{}_label", channel))).unwrap_or(channel),
+                temp: Temp(millidegrees / 1000.0),
+            });
+        }
+    }
+    sensors
+}
+
+pub fn fans() -> Vec<Fan> {
+    let mut fans = Vec::new();
+    for hwmon in hwmon_devices() {
+        for channel in hwmon_channels(&hwmon, "fan") {
+            let rpm = match read_num::<u32, _>(hwmon.join(format!("{}_input", channel))) {
+                Some(rpm) => rpm,
+                None => continue,
+            };
+            fans.push(Fan {
+                label: read_trimmed(hwmon.join(format!("{}_label", channel))).unwrap_or(channel),
+                rpm,
+            });
+        }
+    }
+    fans
+}
+
+// Top level RAPL zones, e.g. intel-rapl:0 which is "package-0". Subzones
+// like intel-rapl:0:0 are already included in their package's counter.
+fn rapl_zones() -> Vec<PathBuf> {
+    let mut zones = fs::read_dir("/sys/class/powercap")
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    name.starts_with("intel-rapl:") && name.matches(':').count() == 1
+                })
+                .map(|entry| entry.path())
+                .collect::<Vec<PathBuf>>()
+        })
+        .unwrap_or_default();
+    zones.sort();
+    zones
+}
+
+pub fn power_draw(interval: Duration) -> Option<Vec<PowerDomain>> {
+    let zones = rapl_zones();
+    if zones.is_empty() {
+        return None;
+    }
+    // energy_uj is only readable by root on most kernels
+    let before = zones
+        .iter()
+        .map(|zone| read_num::<u64, _>(zone.join("energy_uj")))
+        .collect::<Option<Vec<u64>>>()?;
+    std::thread::sleep(interval);
+
+    let secs = interval.as_secs_f64();
+    let domains = zones
+        .iter()
+        .zip(before)
+        .filter_map(|(zone, before)| {
+            let after = read_num::<u64, _>(zone.join("energy_uj"))?;
+            // The counter wraps around at max_energy_range_uj
+            let consumed = if after >= before {
+                after - before
+            } else {
+                read_num::<u64, _>(zone.join("max_energy_range_uj"))? - before + after
+            };
+            Some(PowerDomain {
+                name: read_trimmed(zone.join("name"))?,
+                watts: if secs > 0.0 {
+                    consumed as f64 / 1_000_000.0 / secs
+                } else {
+                    0.0
+                },
+            })
+        })
+        .collect::<Vec<PowerDomain>>();
+
+    if domains.is_empty() {
+        return None;
+    }
+    Some(domains)
+}
+
+// Picks the CPU package sensor, falling back to the first thermal zone
+pub fn get_temp() -> Option<Temp> {
+    let cpu_sensors = temp_sensors()
+        .into_iter()
+        .filter(|sensor| CPU_TEMP_CHIPS.contains(&sensor.chip.as_str()))
+        .collect::<Vec<TempSensor>>();
+    let package = cpu_sensors
+        .iter()
+        .position(|sensor| {
+            ["Package", "Tctl", "Tdie"]
+                .iter()
+                .any(|label| sensor.label.starts_with(label))
+        })
+        .unwrap_or(0);
+    if let Some(sensor) = cpu_sensors.into_iter().nth(package) {
+        return Some(sensor.temp);
+    }
+
+    let millidegrees = read_num::<f64, _>("/sys/class/thermal/thermal_zone0/temp")?;
+    Some(Temp(millidegrees / 1000.0))
+}
+
+fn is_link_local_v6(addr: &Ipv6Addr) -> bool {
+    addr.segments()[0] & 0xffc0 == 0xfe80
+}
+
+// Addresses assigned to interfaces that are up, skipping loopback and
+// link local ones
+fn local_addrs(ipv6: bool) -> Option<Vec<IpAddr>> {
+    use nix::sys::socket::SockAddr;
+
+    Some(
+        nix::ifaddrs::getifaddrs()
+            .ok()?
+            .filter_map(|ifaddr| match ifaddr.address {
+                Some(SockAddr::Inet(addr)) => Some(addr.ip().to_std()),
+                _ => None,
+            })
+            .filter(|addr| match addr {
+                IpAddr::V4(addr) => !ipv6 && !addr.is_loopback() && !addr.is_link_local(),
+                IpAddr::V6(addr) => ipv6 && !addr.is_loopback() && !is_link_local_v6(addr),
+            })
+            .collect(),
+    )
+}
+
+// Only accepts a bare address of the requested family, anything else is
+// most likely a captive portal or error page. Private and loopback answers
+// are fine, they come from internal echo services or local stand-ins.
+fn query_ip_provider(url: &str, timeout: Duration, ipv6: bool) -> Option<IpAddr> {
+    let response = minreq::get(url)
+        .with_timeout(timeout.as_secs().max(1))
+        .send()
+        .ok()?;
+    if response.status_code != 200 {
+        return None;
+    }
+    let addr = response.as_str().ok()?.trim().parse::<IpAddr>().ok()?;
+    match addr {
+        IpAddr::V4(_) if !ipv6 => Some(addr),
+        IpAddr::V6(_) if ipv6 => Some(addr),
+        _ => None,
+    }
+}
+
+pub fn ip(iptype: IpType, ipv6: bool) -> Result<IpAddr, IpError> {
+    let local = local_addrs(ipv6).unwrap_or_default();
+    match iptype {
+        IpType::Public { providers, timeout } => {
+            if local.is_empty() {
+                return Err(IpError::NotConnected);
+            }
+            let defaults = if ipv6 {
+                &PUBLIC_IPV6_PROVIDERS[..]
+            } else {
+                &PUBLIC_IPV4_PROVIDERS[..]
+            };
+            let providers = if providers.is_empty() {
+                defaults.iter().map(|url| url.to_string()).collect()
+            } else {
+                providers.to_vec()
+            };
+            providers
+                .iter()
+                .find_map(|url| query_ip_provider(url, timeout, ipv6))
+                .ok_or(IpError::LookupFailed)
+        }
+        IpType::Private => local
+            .into_iter()
+            .find(|addr| match addr {
+                IpAddr::V4(addr) => addr.is_private(),
+                IpAddr::V6(_) => true,
+            })
+            .ok_or(IpError::NotConnected),
+    }
+}
+
+fn interface_kind(sysfs: &Path) -> InterfaceKind {
+    let uevent = fs::read_to_string(sysfs.join("uevent")).unwrap_or_default();
+    let devtype = uevent
+        .lines()
+        .find_map(|line| line.strip_prefix("DEVTYPE="))
+        .unwrap_or("");
+    match devtype {
+        "wlan" => InterfaceKind::Wifi,
+        "bridge" => InterfaceKind::Bridge,
+        "wireguard" => InterfaceKind::Wireguard,
+        _ if sysfs.join("wireless").exists() => InterfaceKind::Wifi,
+        _ if sysfs.join("tun_flags").exists() => InterfaceKind::Tun,
+        // Only interfaces backed by hardware have a device link
+        _ if sysfs.join("device").exists() => InterfaceKind::Ethernet,
+        _ => InterfaceKind::Virtual,
+    }
+}
+
+fn prefix_len(netmask: &Option<nix::sys::socket::SockAddr>) -> u8 {
+    match netmask {
+        Some(nix::sys::socket::SockAddr::Inet(mask)) => match mask.ip().to_std() {
+            IpAddr::V4(mask) => u32::from(mask).count_ones() as u8,
+            IpAddr::V6(mask) => u128::from(mask).count_ones() as u8,
+        },
+        _ => 0,
+    }
+}
+
+// Interfaces which are up, excluding loopback, in kernel order
+pub fn net_interfaces() -> Option<Vec<NetInterface>> {
+    use nix::net::if_::InterfaceFlags;
+    use nix::sys::socket::SockAddr;
+
+    let mut interfaces: Vec<NetInterface> = Vec::new();
+    for ifaddr in nix::ifaddrs::getifaddrs().ok()? {
+        if !ifaddr.flags.contains(InterfaceFlags::IFF_UP)
+            || ifaddr.flags.contains(InterfaceFlags::IFF_LOOPBACK)
+        {
+            continue;
+        }
+        let idx = match interfaces
+            .iter()
+            .position(|interface| interface.name == ifaddr.interface_name)
+        {
+            Some(idx) => idx,
+            None => {
+                let sysfs = Path::new("/sys/class/net").join(&ifaddr.interface_name);
+                interfaces.push(NetInterface {
+                    name: ifaddr.interface_name.clone(),
+                    kind: interface_kind(&sysfs),
+                    mac: read_trimmed(sysfs.join("address"))
+                        .filter(|mac| !mac.is_empty() && mac != "00:00:00:00:00:00"),
+                    // Reads fail or return -1 when the link is down or virtual
+                    speed: read_num::<i64, _>(sysfs.join("speed"))
+                        .filter(|speed| *speed > 0)
+                        .map(|speed| speed as u32),
+                    addrs: Vec::new(),
+                });
+                interfaces.len() - 1
+            }
+        };
+        if let Some(SockAddr::Inet(addr)) = ifaddr.address {
+            interfaces[idx]
+                .addrs
+                .push((addr.ip().to_std(), prefix_len(&ifaddr.netmask)));
+        }
+    }
+    Some(interfaces)
+}
+
+fn net_counters(interface: &str) -> Option<(u64, u64)> {
+    let stats = Path::new("/sys/class/net")
+        .join(interface)
+        .join("statistics");
+    Some((
+        read_num::<u64, _>(stats.join("rx_bytes"))?,
+        read_num::<u64, _>(stats.join("tx_bytes"))?,
+    ))
+}
+
+pub fn net_speed(interfaces: &[String], interval: Duration) -> Vec<NetSpeed<ByteSize>> {
+    let before = interfaces
+        .iter()
+        .map(|interface| net_counters(interface))
+        .collect::<Vec<Option<(u64, u64)>>>();
+    std::thread::sleep(interval);
+
+    let secs = interval.as_secs_f64();
+    let rate = |delta: u64| {
+        if secs > 0.0 {
+            ByteSize::b((delta as f64 / secs) as u64)
+        } else {
+            ByteSize::b(0)
+        }
+    };
+    interfaces
+        .iter()
+        .zip(before)
+        .filter_map(|(interface, before)| {
+            let (rx_before, tx_before) = before?;
+            let (rx, tx) = net_counters(interface)?;
+            Some(NetSpeed {
+                interface: interface.clone(),
+                rx_rate: rate(rx.saturating_sub(rx_before)),
+                tx_rate: rate(tx.saturating_sub(tx_before)),
+                rx_total: ByteSize::b(rx),
+                tx_total: ByteSize::b(tx),
+            })
+        })
+        .collect()
+}
+
+// Default routes sorted by metric, IPv4 first
+pub fn default_routes() -> Vec<Route> {
+    let mut routes_v4 = Vec::new();
+    // Iface Destination Gateway Flags RefCnt Use Metric Mask ..., with
+    // addresses as hex of the raw network order value
+    let route = fs::read_to_string("/proc/net/route").unwrap_or_default();
+    for line in route.lines().skip(1) {
+        let fields = line.split_whitespace().collect::<Vec<&str>>();
+        if fields.len() < 8 || fields[1] != "00000000" || fields[7] != "00000000" {
+            continue;
+        }
+        let gateway = match u32::from_str_radix(fields[2], 16) {
+            Ok(gateway) if gateway != 0 => gateway,
+            _ => continue,
+        };
+        routes_v4.push(Route {
+            interface: fields[0].to_string(),
+            gateway: IpAddr::V4(Ipv4Addr::from(gateway.to_ne_bytes())),
+            metric: fields[6].parse().unwrap_or(0),
+        });
+    }
+
+    let mut routes_v6 = Vec::new();
+    // dest dest_len src src_len next_hop metric refcnt use flags iface
+    let route = fs::read_to_string("/proc/net/ipv6_route").unwrap_or_default();
+    for line in route.lines() {
+        let fields = line.split_whitespace().collect::<Vec<&str>>();
+        if fields.len() < 10 || fields[1] != "00" || fields[9] == "lo" {
+            continue;
+        }
+        let dest = u128::from_str_radix(fields[0], 16).unwrap_or(1);
+        let gateway = u128::from_str_radix(fields[4], 16).unwrap_or(0);
+        if dest != 0 || gateway == 0 {
+            continue;
+        }
+        routes_v6.push(Route {
+            interface: fields[9].to_string(),
+            gateway: IpAddr::V6(Ipv6Addr::from(gateway)),
+            metric: u32::from_str_radix(fields[5], 16).unwrap_or(0),
+        });
+    }
+
+    routes_v4.sort_by_key(|route| route.metric);
+    routes_v6.sort_by_key(|route| route.metric);
+    routes_v4.into_iter().chain(routes_v6).collect()
+}
+
+fn parse_resolv_conf(path: &str) -> Option<DnsInfo> {
+    let data = fs::read_to_string(path).ok()?;
+    let mut dns = DnsInfo {
+        nameservers: Vec::new(),
+        search: Vec::new(),
+    };
+    for line in data.lines() {
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("nameserver") => dns.nameservers.extend(fields.next().map(String::from)),
+            // The last search or domain line wins
+            Some("search") | Some("domain") => dns.search = fields.map(String::from).collect(),
+            _ => {}
+        }
+    }
+    Some(dns)
+}
+
+pub fn dns() -> Option<DnsInfo> {
+    let dns = parse_resolv_conf("/etc/resolv.conf")?;
+    // With systemd-resolved's stub resolver the real upstream servers are
+    // only listed in its own resolv.conf
+    if dns.nameservers.iter().all(|server| server == "127.0.0.53") {
+        if let Some(upstream) = parse_resolv_conf("/run/systemd/resolve/resolv.conf") {
+            if !upstream.nameservers.is_empty() {
+                return Some(upstream);
+            }
+        }
+    }
+    Some(dns)
+}
+
+// Addresses in /proc/net/{tcp,udp}[6] are hex of the raw network order
+// value, printed as native endian 32 bit words
+fn parse_proc_net_addr(addr: &str) -> Option<(IpAddr, u16)> {
+    let mut parts = addr.split(':');
+    let ip = parts.next()?;
+    let port = u16::from_str_radix(parts.next()?, 16).ok()?;
+    let mut bytes = Vec::with_capacity(16);
+    for idx in (0..ip.len()).step_by(8) {
+        let word = u32::from_str_radix(ip.get(idx..idx + 8)?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+    let ip = match bytes.len() {
+        4 => IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(&bytes[..]).ok()?)),
+        16 => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(&bytes[..]).ok()?)),
+        _ => return None,
+    };
+    Some((ip, port))
+}
+
+pub fn listening_sockets(udp: bool) -> Vec<ListeningSocket> {
+    // TCP sockets in the LISTEN state (0A) and unconnected UDP sockets (07)
+    let mut tables = vec![
+        ("tcp", "/proc/net/tcp", "0A"),
+        ("tcp", "/proc/net/tcp6", "0A"),
+    ];
+    if udp {
+        tables.push(("udp", "/proc/net/udp", "07"));
+        tables.push(("udp", "/proc/net/udp6", "07"));
+    }
+
+    let mut sockets = Vec::new();
+    for (protocol, path, listen_state) in tables {
+        let data = fs::read_to_string(path).unwrap_or_default();
+        for line in data.lines().skip(1) {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            if fields.len() < 10 || fields[3] != listen_state {
+                continue;
+            }
+            if let Some((addr, port)) = parse_proc_net_addr(fields[1]) {
+                sockets.push(ListeningSocket {
+                    protocol,
+                    addr,
+                    port,
+                    inode: fields[9].parse().unwrap_or(0),
+                });
+            }
+        }
+    }
+    sockets.sort_by_key(|socket| (socket.port, socket.protocol));
+    sockets
+}
+
+// Maps socket inodes to the name of the owning process. Only processes of
+// the current user are visible unless running as root.
+pub fn socket_owners() -> HashMap<u64, String> {
+    let mut owners = HashMap::new();
+    let procs = match fs::read_dir("/proc") {
+        Ok(procs) => procs,
+        Err(_) => return owners,
+    };
+    for proc in procs.filter_map(|entry| entry.ok()) {
+        let pid_dir = proc.path();
+        let is_pid = proc
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.chars().all(|c| c.is_ascii_digit()));
+        if !is_pid {
+            continue;
+        }
+        let fds = match fs::read_dir(pid_dir.join("fd")) {
+            Ok(fds) => fds,
+            Err(_) => continue,
+        };
+        let mut comm = None;
+        for fd in fds.filter_map(|entry| entry.ok()) {
+            let target = match fs::read_link(fd.path()) {
+                Ok(target) => target.to_string_lossy().to_string(),
+                Err(_) => continue,
+            };
+            let inode = target
+                .strip_prefix("socket:[")
+                .and_then(|inode| inode.strip_suffix(']'))
+                .and_then(|inode| inode.parse::<u64>().ok());
+            if let Some(inode) = inode {
+                if comm.is_none() {
+                    comm = read_trimmed(pid_dir.join("comm"));
+                }
+                if let Some(comm) = &comm {
+                    owners.entry(inode).or_insert_with(|| comm.clone());
+                }
+            }
+        }
+    }
+    owners
+}
+
+// Minimal generic netlink client, just enough to ask nl80211 about the
+// interface an SSID is associated with
+mod genl {
+    use nix::libc;
+    use nix::sys::socket::{self, MsgFlags, SockAddr};
+    use std::convert::TryInto;
+    use std::os::unix::io::RawFd;
+
+    const NLMSG_HDRLEN: usize = 16;
+    const GENL_HDRLEN: usize = 4;
+    const NLMSG_ERROR: u16 = 2;
+    const NLM_F_REQUEST: u16 = 1;
+    const GENL_ID_CTRL: u16 = 0x10;
+    const CTRL_CMD_GETFAMILY: u8 = 3;
+    const CTRL_ATTR_FAMILY_ID: u16 = 1;
+    const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+
+    pub const NL80211_CMD_GET_INTERFACE: u8 = 5;
+    pub const NL80211_ATTR_IFINDEX: u16 = 3;
+    pub const NL80211_ATTR_WIPHY_FREQ: u16 = 38;
+    pub const NL80211_ATTR_SSID: u16 = 52;
+
+    pub struct Socket(RawFd);
+
+    impl Drop for Socket {
+        fn drop(&mut self) {
+            let _ = nix::unistd::close(self.0);
+        }
+    }
+
+    fn align(len: usize) -> usize {
+        (len + 3) & !3
+    }
+
+    impl Socket {
+        pub fn open() -> Option<Socket> {
+            // nix doesn't expose NETLINK_GENERIC as a socket protocol
+            let fd = unsafe {
+                libc::socket(
+                    libc::AF_NETLINK,
+                    libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+                    libc::NETLINK_GENERIC,
+                )
+            };
+            if fd < 0 {
+                return None;
+            }
+            let sock = Socket(fd);
+            let timeout = nix::sys::time::TimeVal::from(libc::timeval {
+                tv_sec: 1,
+                tv_usec: 0,
+            });
+            socket::setsockopt(fd, socket::sockopt::ReceiveTimeout, &timeout).ok()?;
+            socket::connect(fd, &SockAddr::new_netlink(0, 0)).ok()?;
+            Some(sock)
+        }
+
+        // Sends a single request and returns the attributes of the reply
+        pub fn request(
+            &self,
+            family: u16,
+            cmd: u8,
+            attrs: &[(u16, &[u8])],
+        ) -> Option<Vec<(u16, Vec<u8>)>> {
+            let mut payload = vec![cmd, 1, 0, 0];
+            for (kind, value) in attrs {
+                payload.extend_from_slice(&((4 + value.len()) as u16).to_ne_bytes());
+                payload.extend_from_slice(&kind.to_ne_bytes());
+                payload.extend_from_slice(value);
+                payload.resize(align(payload.len()), 0);
+            }
+            let mut msg = Vec::with_capacity(NLMSG_HDRLEN + payload.len());
+            msg.extend_from_slice(&((NLMSG_HDRLEN + payload.len()) as u32).to_ne_bytes());
+            msg.extend_from_slice(&family.to_ne_bytes());
+            msg.extend_from_slice(&NLM_F_REQUEST.to_ne_bytes());
+            msg.extend_from_slice(&1u32.to_ne_bytes());
+            msg.extend_from_slice(&0u32.to_ne_bytes());
+            msg.extend_from_slice(&payload);
+            socket::send(self.0, &msg, MsgFlags::empty()).ok()?;
+
+            let mut buf = vec![0u8; 8192];
+            let len = socket::recv(self.0, &mut buf, MsgFlags::empty()).ok()?;
+            let buf = &buf[..len];
+            let msg_len = u32::from_ne_bytes(buf.get(0..4)?.try_into().ok()?) as usize;
+            let msg_type = u16::from_ne_bytes(buf.get(4..6)?.try_into().ok()?);
+            if msg_type == NLMSG_ERROR || msg_type != family || msg_len > len {
+                return None;
+            }
+
+            let mut attrs = Vec::new();
+            let mut offset = NLMSG_HDRLEN + GENL_HDRLEN;
+            while offset + 4 <= msg_len {
+                let attr_len =
+                    u16::from_ne_bytes(buf[offset..offset + 2].try_into().ok()?) as usize;
+                // The top bits are the nested and byte order flags
+                let attr_type =
+                    u16::from_ne_bytes(buf[offset + 2..offset + 4].try_into().ok()?) & 0x3fff;
+                if attr_len < 4 || offset + attr_len > msg_len {
+                    break;
+                }
+                attrs.push((attr_type, buf[offset + 4..offset + attr_len].to_vec()));
+                offset += align(attr_len);
+            }
+            Some(attrs)
+        }
+
+        pub fn family_id(&self, name: &str) -> Option<u16> {
+            let mut name = name.as_bytes().to_vec();
+            name.push(0);
+            self.request(
+                GENL_ID_CTRL,
+                CTRL_CMD_GETFAMILY,
+                &[(CTRL_ATTR_FAMILY_NAME, &name)],
+            )?
+            .into_iter()
+            .find(|(kind, _)| *kind == CTRL_ATTR_FAMILY_ID)
+            .and_then(|(_, value)| Some(u16::from_ne_bytes(value.get(0..2)?.try_into().ok()?)))
+        }
+    }
+}
+
+// Asks nl80211 for the SSID and frequency of a station interface
+fn nl80211_link(interface: &str) -> Option<(Option<String>, Option<u32>)> {
+    let ifindex = nix::net::if_::if_nametoindex(interface).ok()?;
+    let sock = genl::Socket::open()?;
+    let family = sock.family_id("nl80211")?;
+    let attrs = sock.request(
+        family,
+        genl::NL80211_CMD_GET_INTERFACE,
+        &[(genl::NL80211_ATTR_IFINDEX, &ifindex.to_ne_bytes())],
+    )?;
+
+    let ssid = attrs
+        .iter()
+        .find(|(kind, _)| *kind == genl::NL80211_ATTR_SSID)
+        .map(|(_, value)| String::from_utf8_lossy(value).to_string());
+    let freq = attrs
+        .iter()
+        .find(|(kind, _)| *kind == genl::NL80211_ATTR_WIPHY_FREQ)
+        .and_then(|(_, value)| Some(u32::from_ne_bytes(value.get(0..4)?.try_into().ok()?)));
+    Some((ssid, freq))
+}
+
+fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip the escape sequence up to its final letter
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+// When netlink is unavailable the SSID of the current connection is only
+// known to the network daemon over D-Bus. Their state files (/var/lib/iwd,
+// NetworkManager's keyfiles) are root only and list known networks rather
+// than the active one, so ask the daemon's own CLI instead
+fn iwctl_ssid(interface: &str) -> Option<(String, Option<u32>)> {
+    let output = std::process::Command::new("iwctl")
+        .args(["station", interface, "show"])
+        .output()
+        .ok()?;
+    let stdout = strip_ansi(&String::from_utf8_lossy(&output.stdout));
+    // Property rows are padded with spaces
+    let property = |name: &str| {
+        stdout.lines().find_map(|line| {
+            let value = line.trim().strip_prefix(name)?;
+            Some(value.trim().to_string()).filter(|value| !value.is_empty())
+        })
+    };
+    let ssid = property("Connected network")?;
+    let freq = property("Frequency").and_then(|freq| freq.parse::<u32>().ok());
+    Some((ssid, freq))
+}
+
+fn nmcli_ssid(interface: &str) -> Option<(String, Option<u32>)> {
+    let output = std::process::Command::new("nmcli")
+        .args([
+            "-t",
+            "-f",
+            "ACTIVE,SSID,FREQ",
+            "device",
+            "wifi",
+            "list",
+            "ifname",
+            interface,
+        ])
+        .args(["--rescan", "no"])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    // Colons inside the SSID are escaped with a backslash
+    let line = stdout.lines().find(|line| line.starts_with("yes:"))?;
+    let fields = line["yes:".len()..].replace("\\:", "\0");
+    let mut fields = fields.splitn(2, ':');
+    let ssid = fields.next()?.replace('\0', ":");
+    let freq = fields
+        .next()
+        .and_then(|freq| freq.split_whitespace().next()?.parse::<u32>().ok());
+    Some((ssid, freq))
+}
+
+pub fn wifi_info() -> Option<Vec<WifiInfo>> {
+    let wireless = fs::read_to_string("/proc/net/wireless").unwrap_or_default();
+    let mut interfaces = fs::read_dir("/sys/class/net")
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| matches!(interface_kind(&entry.path()), InterfaceKind::Wifi))
+        .filter(|entry| read_trimmed(entry.path().join("operstate")).as_deref() == Some("up"))
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect::<Vec<String>>();
+    interfaces.sort();
+
+    Some(
+        interfaces
+            .into_iter()
+            .map(|interface| {
+                // "wlan0: 0000   54.  -56.  -256 ...", the link quality scale
+                // depends on the driver so it is derived from the signal level
+                let stats = wireless
+                    .lines()
+                    .find(|line| line.trim_start().starts_with(&format!("{}:", interface)))
+                    .map(|line| {
+                        line.split_whitespace()
+                            .map(|field| field.trim_end_matches('.').to_string())
+                            .collect::<Vec<String>>()
+                    })
+                    .unwrap_or_default();
+                let signal = stats
+                    .get(3)
+                    .and_then(|level| level.parse::<i32>().ok())
+                    .filter(|level| *level < 0);
+                // Same mapping as NetworkManager, -100 dBm is 0% and -50 dBm 100%
+                let quality = signal.map(|level| ((level + 100) * 2).clamp(0, 100) as u8);
+
+                let (ssid, freq_mhz) = match nl80211_link(&interface) {
+                    Some((Some(ssid), freq)) => (Some(ssid), freq),
+                    _ => match iwctl_ssid(&interface).or_else(|| nmcli_ssid(&interface)) {
+                        Some((ssid, freq)) => (Some(ssid), freq),
+                        None => (None, None),
+                    },
+                };
+
+                WifiInfo {
+                    interface,
+                    ssid,
+                    quality,
+                    signal,
+                    freq_mhz,
+                }
+            })
+            .collect(),
+    )
+}
+
+pub fn packages(distro: &str) -> Option<usize> {
     match distro {
         "Arch Linux" => {
             let output = std::process::Command::new("pacman")
@@ -296,39 +2814,124 @@ pub fn packages(distro: &str) -> Option<usize> {
     }
 }
 
-#[cfg(feature = "battery")]
-pub fn battery_info() -> Option<BatteryInfo> {
+// Reads a µWh energy value, converting µAh charge values for batteries
+// which only report charge
+fn battery_energy(path: &Path, key: &str, voltage: Option<f64>) -> Option<f64> {
+    read_num::<f64, _>(path.join(format!("energy_{}", key)))
+        .or_else(|| Some(read_num::<f64, _>(path.join(format!("charge_{}", key)))? * voltage?))
+        .map(|uwh| uwh / 1_000_000.0)
+}
+
+fn sysfs_battery(path: &Path) -> Option<BatteryInfo> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let status = match read_trimmed(path.join("status"))?.as_str() {
+        "Charging" => BatteryStatus::Charging,
+        "Discharging" => BatteryStatus::Discharging,
+        "Not charging" => BatteryStatus::NotCharging,
+        "Full" => BatteryStatus::Full,
+        _ => BatteryStatus::Unknown,
+    };
+    // Voltages are reported in µV
+    let voltage = read_num::<f64, _>(path.join("voltage_min_design"))
+        .or_else(|| read_num::<f64, _>(path.join("voltage_now")))
+        .map(|uv| uv / 1_000_000.0);
+    let energy_now = battery_energy(path, "now", voltage);
+    let energy_full = battery_energy(path, "full", voltage);
+    let energy_full_design = battery_energy(path, "full_design", voltage);
+    let power_now = read_num::<f64, _>(path.join("power_now"))
+        .map(|uw| uw / 1_000_000.0)
+        .or_else(|| {
+            let current = read_num::<f64, _>(path.join("current_now"))? / 1_000_000.0;
+            let voltage = read_num::<f64, _>(path.join("voltage_now"))? / 1_000_000.0;
+            Some(current * voltage)
+        })
+        // Some drivers report a negative draw while discharging
+        .map(f64::abs);
+    let percent = read_num::<u8, _>(path.join("capacity")).or_else(|| {
+        let ratio = energy_now? / energy_full?;
+        Some((ratio * 100.0).round().min(100.0) as u8)
+    })?;
+
+    Some(BatteryInfo {
+        name,
+        status,
+        percent,
+        energy_now,
+        energy_full,
+        energy_full_design,
+        power_now,
+        cycle_count: read_num::<u32, _>(path.join("cycle_count")).filter(|count| *count > 0),
+    })
+}
+
+pub fn power_supply() -> Option<PowerSupplyInfo> {
+    let mut supplies = fs::read_dir("/sys/class/power_supply")
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .collect::<Vec<PathBuf>>()
+        })
+        .unwrap_or_default();
+    supplies.sort();
+
+    let mut batteries = Vec::new();
+    let mut ac_online = None;
+    for supply in supplies {
+        match read_trimmed(supply.join("type")).as_deref() {
+            // Peripheral batteries (mice, headsets) have a scope of "Device"
+            Some("Battery") if read_trimmed(supply.join("scope")).as_deref() != Some("Device") => {
+                batteries.extend(sysfs_battery(&supply));
+            }
+            Some("Mains") => {
+                let online = read_trimmed(supply.join("online")).as_deref() == Some("1");
+                ac_online = Some(ac_online.unwrap_or(false) || online);
+            }
+            _ => {}
+        }
+    }
+
+    #[cfg(feature = "upower")]
+    if batteries.is_empty() {
+        batteries.extend(upower_battery_info());
+    }
+
+    Some(PowerSupplyInfo {
+        batteries,
+        ac_online,
+    })
+}
+
+#[cfg(feature = "upower")]
+pub fn upower_battery_info() -> Option<BatteryInfo> {
     use std::process::Command;
     let mut state: BatteryInfo = BatteryInfo {
+        name: String::new(),
         status: BatteryStatus::Full,
         percent: 0,
+        energy_now: None,
+        energy_full: None,
+        energy_full_design: None,
+        power_now: None,
+        cycle_count: None,
     };
 
-    let raw_path = Command::new("upower")
-        .args(["-e"])
-        .output()
-        .expect("Battery module failed. Please make sure upower is installed.")
-        .stdout;
+    let raw_path = Command::new("upower").args(["-e"]).output().ok()?.stdout;
 
-    let paths = String::from_utf8(raw_path)
-        .expect("Couldn't get battery system info: Invalid upower output.");
-    let path: &str = paths
-        .split('\n')
-        .filter(|s| s.contains("BAT"))
-        .last()
-        .expect("Couldn't get battery system info: No battery system installed.");
+    let paths = String::from_utf8(raw_path).ok()?;
+    let path: &str = paths.split('\n').rfind(|s| s.contains("BAT"))?;
+    state.name = path.rsplit('_').next().unwrap_or(path).to_string();
 
     let raw_stats = Command::new("upower")
         .args(["-i", path])
         .output()
-        .unwrap()
+        .ok()?
         .stdout;
-    let stat_string = String::from_utf8(raw_stats)
-        .expect("Couldn't get battery system info: Invalid upower output.");
+    let stat_string = String::from_utf8(raw_stats).ok()?;
     let stats: Vec<String> = stat_string
         .split('\n')
         .filter(|l| l.contains("percentage") | l.contains("state"))
-        .map(|l| l.split(':').last().unwrap_or("").trim().to_string())
+        .map(|l| l.split(':').next_back().unwrap_or("").trim().to_string())
         .collect();
 
     for mut s in stats.into_iter() {
@@ -336,6 +2939,8 @@ pub fn battery_info() -> Option<BatteryInfo> {
         match &str[..] {
             "charging" => state.status = BatteryStatus::Charging,
             "discharging" => state.status = BatteryStatus::Discharging,
+            "pending-charge" => state.status = BatteryStatus::NotCharging,
+            "unknown" => state.status = BatteryStatus::Unknown,
             _ if s.pop() == Some('%') => state.percent = s.parse().unwrap_or(0),
             _ => {}
         }
@@ -343,3 +2948,72 @@ pub fn battery_info() -> Option<BatteryInfo> {
 
     Some(state)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_utmp_record() {
+        let mut record = vec![0u8; UTMP_SIZE];
+        record[0..2].copy_from_slice(&UTMP_USER_PROCESS.to_ne_bytes());
+        record[4..8].copy_from_slice(&1234i32.to_ne_bytes());
+        record[8..13].copy_from_slice(b"pts/0");
+        record[44..49].copy_from_slice(b"alice");
+        record[76..85].copy_from_slice(b"10.0.0.42");
+        record[340..344].copy_from_slice(&1_600_000_000i32.to_ne_bytes());
+
+        // A trailing partial record is ignored
+        record.extend_from_slice(&[0u8; 10]);
+        let entries = parse_utmp(&record);
+        assert_eq!(entries.len(), 1);
+
+        let entry = &entries[0];
+        assert_eq!(entry.kind, UTMP_USER_PROCESS);
+        assert_eq!(entry.pid, 1234);
+        assert_eq!(entry.line, "pts/0");
+        assert_eq!(entry.user, "alice");
+        assert_eq!(entry.host, "10.0.0.42");
+        assert_eq!(entry.time.timestamp(), 1_600_000_000);
+    }
+
+    struct FakeManager {
+        running: Option<Vec<String>>,
+        failed: Option<Vec<String>>,
+    }
+
+    impl ServiceManager for FakeManager {
+        fn running_units(&self) -> Option<Vec<String>> {
+            self.running.clone()
+        }
+
+        fn failed_units(&self) -> Option<Vec<String>> {
+            self.failed.clone()
+        }
+    }
+
+    #[test]
+    fn counts_services() {
+        let manager = FakeManager {
+            running: Some(vec!["dbus.service".into(), "sshd.service".into()]),
+            failed: Some(vec!["foo.service".into()]),
+        };
+        let status = services(&manager).unwrap();
+        assert_eq!(status.running, 2);
+        assert_eq!(status.failed, vec!["foo.service"]);
+
+        let manager = FakeManager {
+            running: Some(Vec::new()),
+            failed: None,
+        };
+        assert!(services(&manager).is_none());
+    }
+
+    #[test]
+    fn parses_systemctl_json() {
+        let json = br#"[{"unit":"dbus.service","load":"loaded","active":"active","sub":"running","description":"D-Bus System Message Bus"}]"#;
+        assert_eq!(parse_units(json), Some(vec!["dbus.service".to_string()]));
+        assert_eq!(parse_units(b"[]"), Some(Vec::new()));
+        assert_eq!(parse_units(b"not json"), None);
+    }
+}